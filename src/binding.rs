@@ -91,6 +91,38 @@ pub(crate) fn open_budget() -> Result<Budget> {
 }
 
 
+/// Encrypts content of a local file with the instance's key.
+/// 
+/// * `budget` - budget, which key is used
+/// * `plaintext` - data to encrypt
+pub(crate) fn encrypt_local(budget: &Budget, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let engine = CryptographicEngine::open(&ensure_location()?)?;
+    let key = engine.lookup_key(budget.key_id())?;
+
+    Ok(engine.encrypt(&key, plaintext)?.as_bytes().to_vec())
+}
+
+
+/// Decrypts content of a local file with the instance's key.
+/// 
+/// * `budget` - budget, which key is used
+/// * `ciphertext` - data to decrypt
+pub(crate) fn decrypt_local(budget: &Budget, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let engine = CryptographicEngine::open(&ensure_location()?)?;
+    let key = engine.lookup_key(budget.key_id())?;
+
+    Ok(engine.decrypt(&key, ciphertext)?.as_bytes().to_vec())
+}
+
+
+/// Returns root directory of application's storage.
+/// 
+/// Local files, that are not synchronized, are stored here too.
+pub(crate) fn storage_root() -> Result<std::path::PathBuf> {
+    Ok(ensure_location()?.root().to_path_buf())
+}
+
+
 fn ensure_location() -> Result<location::HomeLocation> {
     let loc = location::HomeLocation::new();
     if loc.exists() {
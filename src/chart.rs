@@ -0,0 +1,287 @@
+use colored::Colorize;
+
+use crate::console::{self, Output, WritePaged};
+use crate::error::Result;
+
+
+/// Characters used to draw horizontal bars with 1/8 precision.
+const BAR_PARTS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Characters used to draw sparklines from the lowest to the highest value.
+const SPARK_PARTS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Character used to draw an empty part of a progress bar.
+const EMPTY_PART: char = '░';
+
+/// Terminal width used if it cannot be determined.
+const DEFAULT_WIDTH: usize = 80;
+
+/// Minimal width of a bar regardless of terminal width.
+const MIN_BAR_WIDTH: usize = 10;
+
+
+/// Single row of a bar chart.
+pub(crate) struct ChartRow {
+    /// Label displayed before the bar.
+    pub label: String,
+
+    /// Value, that defines bar length. Sign defines bar color.
+    pub value: isize,
+
+    /// Optional trend (e.g. sparkline) displayed after the value.
+    pub trend: Option<String>,
+}
+
+
+/// Horizontal bar chart scaled to terminal width.
+pub(crate) struct BarChart {
+    rows: Vec<ChartRow>,
+}
+
+
+/// Single row of a progress chart.
+pub(crate) struct ProgressRow {
+    /// Label displayed before the bar.
+    pub label: String,
+
+    /// Current progress value.
+    pub current: isize,
+
+    /// Value, that corresponds to a full bar.
+    pub limit: isize,
+}
+
+
+/// Chart with progress bars scaled to terminal width.
+pub(crate) struct ProgressChart {
+    rows: Vec<ProgressRow>,
+}
+
+
+impl BarChart {
+    /// Creates a chart from rows.
+    ///
+    /// * `rows` - chart rows
+    pub(crate) fn new(rows: Vec<ChartRow>) -> Self {
+        BarChart { rows: rows }
+    }
+}
+
+
+impl ProgressChart {
+    /// Creates a chart from rows.
+    ///
+    /// * `rows` - chart rows
+    pub(crate) fn new(rows: Vec<ProgressRow>) -> Self {
+        ProgressChart { rows: rows }
+    }
+}
+
+
+impl WritePaged for BarChart {
+    fn write_paged(&self, output: &mut Output) -> Result<()> {
+        let label_width = max_width(self.rows.iter().map(|row| &row.label));
+        let value_width = max_width(self.rows.iter().map(|row| console::format_amount(row.value)));
+        let trend_width = max_width(self.rows.iter().filter_map(|row| row.trend.as_ref()));
+
+        let bar_width = bar_width(label_width + value_width + trend_width + 3);
+        let max_value = self.rows
+            .iter()
+            .map(|row| row.value.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+
+        let mut result = String::new();
+        for row in &self.rows {
+            let bar = paint_by_sign(bar(row.value.unsigned_abs(), max_value, bar_width), row.value);
+
+            result.push_str(&format!("{} {} {} {}\n",
+                pad(&row.label, label_width),
+                pad(&bar, bar_width),
+                pad_left(&console::colorize_amount(row.value).to_string(), value_width),
+                row.trend.as_deref().unwrap_or("")
+            ));
+        }
+
+        result.write_paged(output)
+    }
+}
+
+
+impl WritePaged for ProgressChart {
+    fn write_paged(&self, output: &mut Output) -> Result<()> {
+        let label_width = max_width(self.rows.iter().map(|row| &row.label));
+        let status_width = max_width(self.rows.iter().map(progress_status));
+
+        let bar_width = bar_width(label_width + status_width + 2);
+
+        let mut result = String::new();
+        for row in &self.rows {
+            let remains = row.limit - row.current;
+            let bar = paint_by_sign(progress_bar(row.current, row.limit, bar_width), remains);
+
+            result.push_str(&format!("{} {} {}\n",
+                pad(&row.label, label_width),
+                bar,
+                progress_status(row)
+            ));
+        }
+
+        result.write_paged(output)
+    }
+}
+
+
+/// Builds a sparkline for a sequence of values.
+///
+/// Each value is represented by a single character, which
+/// height is proportional to the value relatively to minimal
+/// and maximal values in the sequence.
+///
+/// * `values` - values to build sparkline for
+pub(crate) fn sparkline(values: &[isize]) -> String {
+    let (min, max) = match (values.iter().min(), values.iter().max()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => return String::new()
+    };
+
+    let range = (max - min) as f64;
+    let last = SPARK_PARTS.len() - 1;
+
+    values
+        .iter()
+        .map(|value| {
+            if range == 0.0 {
+                return SPARK_PARTS[last / 2];
+            }
+
+            let idx = ((value - min) as f64 / range * last as f64).round() as usize;
+            SPARK_PARTS[idx.min(last)]
+        })
+        .collect()
+}
+
+
+/// Returns current terminal width or some reasonable default.
+pub(crate) fn terminal_width() -> usize {
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return width as usize;
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+
+fn bar_width(reserved: usize) -> usize {
+    terminal_width()
+        .saturating_sub(reserved)
+        .max(MIN_BAR_WIDTH)
+}
+
+
+fn bar(value: usize, max_value: usize, width: usize) -> String {
+    if max_value == 0 {
+        return String::new();
+    }
+
+    //
+    // Bar length is measured in eighths of a character
+    //
+
+    let eighths = (value as f64 / max_value as f64 * (width * 8) as f64).round() as usize;
+    let (full, partial) = (eighths / 8, eighths % 8);
+
+    let mut result = BAR_PARTS[7].to_string().repeat(full);
+    if partial != 0 {
+        result.push(BAR_PARTS[partial - 1]);
+    }
+
+    result
+}
+
+
+fn progress_bar(current: isize, limit: isize, width: usize) -> String {
+    let filled = match limit {
+        l if l <= 0 => width,
+        l => ((current.max(0) as f64 / l as f64 * width as f64).round() as usize).min(width)
+    };
+
+    let mut result = BAR_PARTS[7].to_string().repeat(filled);
+    result.push_str(&EMPTY_PART.to_string().repeat(width - filled));
+
+    result
+}
+
+
+fn progress_status(row: &ProgressRow) -> String {
+    let percent = match row.limit {
+        0 => 100,
+        l => row.current * 100 / l
+    };
+
+    format!("{} / {} ({}%)", row.current, row.limit, percent)
+}
+
+
+fn paint_by_sign(bar: String, value: isize) -> String {
+    console::paint_by_sign(bar.normal(), value)
+        .to_string()
+}
+
+
+fn max_width<I, S>(items: I) -> usize
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>
+{
+    items
+        .map(|item| item.as_ref().chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+
+fn pad(s: &str, width: usize) -> String {
+    //
+    // Styled strings contain escape sequences, so padding is
+    // computed using visible characters only
+    //
+
+    let length = strip_styles(s).chars().count();
+    format!("{}{}", s, " ".repeat(width.saturating_sub(length)))
+}
+
+
+fn pad_left(s: &str, width: usize) -> String {
+    let length = strip_styles(s).chars().count();
+    format!("{}{}", " ".repeat(width.saturating_sub(length)), s)
+}
+
+
+fn strip_styles(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            //
+            // Skip the whole escape sequence up to its final letter
+            //
+
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+
+            continue;
+        }
+
+        result.push(c);
+    }
+
+    result
+}
@@ -2,9 +2,22 @@ use libbdgt::datetime::Clock;
 use libbdgt::storage::{Account, MetaInfo};
 
 use super::command::{Command, CommandInternal};
-use crate::error::Result;
-use crate::console;
+use crate::error::{Result, Error};
+use crate::console::{self, Review};
+use crate::journal;
 use crate::binding;
+use crate::errors;
+
+
+/// Editable field of an account.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Name of the account.
+    Name,
+
+    /// Initial balance of the account.
+    InitialBalance,
+}
 
 
 /// Account addition command. Adds a new account in interactive mode.
@@ -19,14 +32,18 @@ impl Command for AddAccount {
     fn add_args(command: clap::Command) -> clap::Command {
         command
             .arg(clap::arg!(-m --multi "add several accounts one-by-one"))
+            .arg(clap::arg!(-y --yes "save account(s) without confirmation"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
-        let multi = Self::parse_args(matches)?;
+        let (multi, yes) = Self::parse_args(matches)?;
         let budget = binding::open_budget()?;
 
         while {
-            budget.add_account(&Self::input_account()?)?;
+            match Self::input_account(yes)? {
+                Some(account) => { journal::add_account(&budget, &account)?; },
+                None => println!("Account is discarded.")
+            }
 
             //
             // If multiple accounts requested, then ask if one needs to add another one
@@ -41,26 +58,74 @@ impl Command for AddAccount {
 
 
 impl CommandInternal for AddAccount {
-    type ParsedArgs = bool;
+    type ParsedArgs = (bool, bool);
 
     fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
-        Self::get_one(matches, "multi")
+        Ok((Self::get_one(matches, "multi")?, Self::get_one(matches, "yes")?))
     }
 }
 
 
 impl AddAccount {
-    fn input_account() -> Result<Account> {
-        let name = console::input_string_with_prompt("Enter account name")?;
-        let initial_balance = console::input_number_with_prompt("Enter initial balance")?;
+    /// Runs account creation flow inline and returns the created account.
+    /// Returns `None` if user discards the account.
+    /// 
+    /// * `budget` - budget to add account into
+    /// * `yes` - save account without confirmation
+    pub(crate) fn create_account(budget: &binding::Budget, yes: bool) -> Result<Option<Account>> {
+        let account = match Self::input_account(yes)? {
+            Some(account) => account,
+            None => return Ok(None)
+        };
+
+        let id = journal::add_account(budget, &account)?;
+
+        budget.accounts()?
+            .into_iter()
+            .find(|account| account.id == Some(id))
+            .map(Some)
+            .ok_or(Error::from_message(errors::CREATED_ITEM_NOT_FOUND))
+    }
 
-        Ok(Account { 
+    fn input_account(yes: bool) -> Result<Option<Account>> {
+        let mut name = Self::input_name()?;
+        let mut initial_balance = Self::input_initial_balance()?;
+
+        //
+        // Let user check everything before saving
+        //
+
+        if !yes {
+            loop {
+                let fields = [
+                    (Field::Name, "Name", name.clone()),
+                    (Field::InitialBalance, "Initial balance", console::colorize_amount(initial_balance).to_string())
+                ];
+
+                match console::review_with_prompt(&fields, "Save account?")? {
+                    Review::Save => break,
+                    Review::Discard => return Ok(None),
+                    Review::Edit(Field::Name) => name = Self::input_name()?,
+                    Review::Edit(Field::InitialBalance) => initial_balance = Self::input_initial_balance()?
+                }
+            }
+        }
+
+        Ok(Some(Account { 
             id: None,
             name: name, 
             balance: initial_balance,
             initial_balance: initial_balance,
             meta_info: MetaInfo::new(Some(Clock::now()), None, None)
-        })
+        }))
+    }
+
+    fn input_name() -> Result<String> {
+        console::input_string_with_prompt("Enter account name")
+    }
+
+    fn input_initial_balance() -> Result<isize> {
+        console::input_number_with_prompt("Enter initial balance")
     }
 
     fn needs_another_account() -> Result<bool> {
@@ -1,11 +1,24 @@
 use libbdgt::datetime::Clock;
-use libbdgt::storage::{Category, MetaInfo};
+use libbdgt::storage::{Category, CategoryType, MetaInfo};
 
 use super::command::{Command, CommandInternal};
 use super::common;
-use crate::error::Result;
-use crate::console;
+use crate::error::{Result, Error};
+use crate::console::{self, Review};
+use crate::journal;
 use crate::binding;
+use crate::errors;
+
+
+/// Editable field of a category.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Type of the category.
+    Type,
+
+    /// Name of the category.
+    Name,
+}
 
 
 /// Category addition command. Adds a new category in interactive mode.
@@ -20,14 +33,18 @@ impl Command for AddCategory {
     fn add_args(command: clap::Command) -> clap::Command {
         command
             .arg(clap::arg!(-m --multi "add several categories one-by-one"))
+            .arg(clap::arg!(-y --yes "save category(s) without confirmation"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
-        let multi = Self::parse_args(matches)?;
+        let (multi, yes) = Self::parse_args(matches)?;
         let budget = binding::open_budget()?;
 
         while {
-            budget.add_category(&Self::input_category()?)?;
+            match Self::input_category(None, yes)? {
+                Some(category) => { journal::add_category(&budget, &category)?; },
+                None => println!("Category is discarded.")
+            }
 
             //
             // If multiple categories requested, then ask if one needs to add another one
@@ -42,27 +59,85 @@ impl Command for AddCategory {
 
 
 impl CommandInternal for AddCategory {
-    type ParsedArgs = bool;
+    type ParsedArgs = (bool, bool);
 
     fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
-        Self::get_one(matches, "multi")
+        Ok((Self::get_one(matches, "multi")?, Self::get_one(matches, "yes")?))
     }
 }
 
 
 impl AddCategory {
-    fn input_category() -> Result<Category> {
-        let selection = console::select_from_with_prompt(&common::category_types(), 
-            "Select what type of category you want")?;
-            
-        let name = console::input_string_with_prompt("Enter category name")?;
+    /// Runs category creation flow inline and returns the created category.
+    /// Returns `None` if user discards the category.
+    /// 
+    /// * `budget` - budget to add category into
+    /// * `category_type` - type of the category if it is not requested from user
+    /// * `yes` - save category without confirmation
+    pub(crate) fn create_category(budget: &binding::Budget, category_type: Option<CategoryType>, yes: bool) 
+        -> Result<Option<Category>> 
+    {
+        let category = match Self::input_category(category_type, yes)? {
+            Some(category) => category,
+            None => return Ok(None)
+        };
+
+        let id = journal::add_category(budget, &category)?;
+
+        budget.categories()?
+            .into_iter()
+            .find(|category| category.id == Some(id))
+            .map(Some)
+            .ok_or(Error::from_message(errors::CREATED_ITEM_NOT_FOUND))
+    }
 
-        Ok(Category { 
+    fn input_category(fixed_type: Option<CategoryType>, yes: bool) -> Result<Option<Category>> {
+        let mut category_type = match fixed_type {
+            Some(category_type) => category_type,
+            None => Self::input_type()?
+        };
+
+        let mut name = Self::input_name()?;
+
+        //
+        // Let user check everything before saving. Fixed type
+        // cannot be changed.
+        //
+
+        if !yes {
+            loop {
+                let mut fields = vec![(Field::Name, "Name", name.clone())];
+
+                if fixed_type.is_none() {
+                    fields.insert(0, (Field::Type, "Type", common::category_type_to_string(category_type)));
+                }
+
+                match console::review_with_prompt(&fields, "Save category?")? {
+                    Review::Save => break,
+                    Review::Discard => return Ok(None),
+                    Review::Edit(Field::Type) => category_type = Self::input_type()?,
+                    Review::Edit(Field::Name) => name = Self::input_name()?
+                }
+            }
+        }
+
+        Ok(Some(Category { 
             id: None,
             name: name, 
-            category_type: common::category_type_by_index(selection)?,
+            category_type: category_type,
             meta_info: MetaInfo::new(Some(Clock::now()), None, None)
-        })
+        }))
+    }
+
+    fn input_type() -> Result<CategoryType> {
+        let selection = console::select_from_with_prompt(&common::category_types(), 
+            "Select what type of category you want")?;
+
+        Ok(common::category_type_by_index(selection)?)
+    }
+
+    fn input_name() -> Result<String> {
+        console::input_string_with_prompt("Enter category name")
     }
 
     fn needs_another_category() -> Result<bool> {
@@ -4,11 +4,26 @@ use libbdgt::storage::{Plan, Category, CategoryType, MetaInfo};
 use super::command::{Command, CommandInternal};
 use super::common;
 use crate::error::{Result, Error};
-use crate::console;
+use crate::console::{self, Review};
+use crate::journal;
 use crate::binding;
 use crate::errors;
 
 
+/// Editable field of a plan.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Category of the plan.
+    Category,
+
+    /// Name of the plan.
+    Name,
+
+    /// Amount limit of the plan.
+    AmountLimit,
+}
+
+
 /// Plan addition command. Adds a new plan(s) in interactive mode.
 pub(crate) struct AddPlan;
 
@@ -21,10 +36,11 @@ impl Command for AddPlan {
     fn add_args(command: clap::Command) -> clap::Command {
         command
             .arg(clap::arg!(-m --multi "add several plans one-by-one"))
+            .arg(clap::arg!(-y --yes "save plan(s) without confirmation"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
-        let multi = Self::parse_args(matches)?;
+        let (multi, yes) = Self::parse_args(matches)?;
         let budget = binding::open_budget()?;
 
         //
@@ -38,7 +54,10 @@ impl Command for AddPlan {
         }
 
         while {
-            budget.add_plan(&Self::input_plan(&categories)?)?;
+            match Self::input_plan(&categories, yes)? {
+                Some(plan) => { journal::add_plan(&budget, &plan)?; },
+                None => println!("Plan is discarded.")
+            }
 
             //
             // If multiple plans requested, then ask if one needs to add another one
@@ -53,20 +72,56 @@ impl Command for AddPlan {
 
 
 impl CommandInternal for AddPlan {
-    type ParsedArgs = bool;
+    type ParsedArgs = (bool, bool);
 
     fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
-        Self::get_one(matches, "multi")
+        Ok((Self::get_one(matches, "multi")?, Self::get_one(matches, "yes")?))
     }
 }
 
 
 impl AddPlan {
-    fn input_plan(categories: &Vec<Category>) -> Result<Plan> {
+    fn input_plan(categories: &Vec<Category>, yes: bool) -> Result<Option<Plan>> {
+        //
+        // Ask for category, plan name and amount limit
         //
-        // Ask for category
+
+        let mut category = Self::select_category(categories)?;
+        let mut name = Self::input_name()?;
+        let mut amount_limit = Self::input_amount_limit()?;
+
+        //
+        // Let user check everything before saving
         //
 
+        if !yes {
+            loop {
+                let fields = [
+                    (Field::Category, "Category", categories[category].name.clone()),
+                    (Field::Name, "Name", name.clone()),
+                    (Field::AmountLimit, "Amount limit", amount_limit.to_string())
+                ];
+
+                match console::review_with_prompt(&fields, "Save plan?")? {
+                    Review::Save => break,
+                    Review::Discard => return Ok(None),
+                    Review::Edit(Field::Category) => category = Self::select_category(categories)?,
+                    Review::Edit(Field::Name) => name = Self::input_name()?,
+                    Review::Edit(Field::AmountLimit) => amount_limit = Self::input_amount_limit()?
+                }
+            }
+        }
+
+        Ok(Some(Plan {
+            id: None,
+            category_id: categories[category].id.unwrap(),
+            name: name,
+            amount_limit: amount_limit,
+            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
+        }))
+    }
+
+    fn select_category(categories: &Vec<Category>) -> Result<usize> {
         let printable_categories: Vec<_> = categories
             .iter()
             .map(|category| {
@@ -74,27 +129,22 @@ impl AddPlan {
             })
             .collect();
 
-        let selection = console::select_from_with_prompt(&printable_categories, 
-            "Which category does plan belong to?")?;
+        console::fuzzy_select_from_with_prompt(&printable_categories, 
+            "Which category does plan belong to?")
+    }
 
-        let category = &categories[selection];
+    fn input_name() -> Result<String> {
+        console::input_string_with_prompt("Plan name")
+    }
 
+    fn input_amount_limit() -> Result<isize> {
         //
-        // Ask for plan name and amount limit. Amount limit is intended to be positive.
+        // Amount limit is intended to be positive.
         // If it doesn't, then I just negate the value.
         //
 
-        let name = console::input_string_with_prompt("Plan name")?;
-        let amount_limit = console::input_number_with_prompt("Amount limit (a negative value will be negated)")
-            .map(isize::abs)?;
-
-        Ok(Plan {
-            id: None,
-            category_id: category.id.unwrap(),
-            name: name,
-            amount_limit: amount_limit,
-            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
-        })
+        console::input_number_with_prompt("Amount limit (a negative value will be negated)")
+            .map(isize::abs)
     }
 
     fn needs_another_plan() -> Result<bool> {
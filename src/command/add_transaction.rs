@@ -1,12 +1,43 @@
-use libbdgt::datetime::Clock;
-use libbdgt::storage::{Transaction, CategoryType, Category, Account, MetaInfo};
+use libbdgt::datetime::{Clock, Timestamp};
+use libbdgt::storage::{Transaction, CategoryType, Category, Account, MetaInfo, Id};
 
 use super::command::{Command, CommandInternal};
+use super::add_category::AddCategory;
+use super::add_account::AddAccount;
 use super::common;
-use crate::error::{Result, Error};
-use crate::console;
+use crate::suggestions::Suggestions;
+use crate::templates::{Template, Templates};
+use crate::error::Result;
+use crate::console::{self, Review};
+use crate::journal;
 use crate::binding;
-use crate::errors;
+
+
+/// Selection menu entry, that creates a new category.
+const NEW_CATEGORY: &str = "+ New category…";
+
+/// Selection menu entry, that creates a new account.
+const NEW_ACCOUNT: &str = "+ New account…";
+
+
+/// Editable field of a transaction.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Description of the transaction.
+    Description,
+
+    /// Amount of money.
+    Amount,
+
+    /// Account of the transaction.
+    Account,
+
+    /// Category of the transaction.
+    Category,
+
+    /// Date and time of the transaction.
+    Timestamp,
+}
 
 
 /// Structure with command parameters.
@@ -19,6 +50,12 @@ pub(crate) struct Parameters {
 
     /// Input transaction of specific category.
     category_type: Option<CategoryType>,
+
+    /// Name of a template to fill transaction from.
+    template: Option<String>,
+
+    /// Save transactions without confirmation.
+    yes: bool,
 }
 
 
@@ -43,29 +80,52 @@ impl Command for AddTransaction {
                 clap::arg!(-o --outcome "add outcome transaction(s)")
                     .conflicts_with("income")
             )
+            .arg(
+                clap::arg!(-t --template <NAME> "fill transaction(s) from template, asking only for blank fields")
+                    .conflicts_with_all(["income", "outcome"])
+            )
+            .arg(clap::arg!(-y --yes "save transaction(s) without confirmation"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
         let parameters = Self::parse_args(matches)?;
         let budget = binding::open_budget()?;
 
-        let accounts = budget.accounts()?;
+        //
+        // Missing accounts and categories can be created inline,
+        // hence empty lists are not an error here
+        //
 
-        if accounts.is_empty() {
-            return Err(Error::from_message(errors::NO_ACCOUNTS));
-        }
+        let mut accounts = budget.accounts()?;
 
-        let categories = match parameters.category_type {
+        let mut categories = match parameters.category_type {
             Some(category_type) => budget.categories_of(category_type)?,
             None => budget.categories()?
         };
 
-        if categories.is_empty() {
-            return Err(Error::from_message(errors::NO_CATEGORIES));
-        }
+        //
+        // Earlier transactions are used to complete descriptions
+        // and to preselect category and account
+        //
+
+        let mut suggestions = Suggestions::collect(&budget.transactions()?);
+
+        let template = match &parameters.template {
+            Some(name) => Templates::load(&budget)?.find(name)?.clone(),
+            None => Template::default()
+        };
 
         while {
-            budget.add_transaction(&Self::input_transaction(parameters.full, &accounts, &categories)?)?;
+            let transaction = Self::input_transaction(&budget, &parameters, &template, &suggestions, 
+                &mut accounts, &mut categories)?;
+
+            match transaction {
+                Some(transaction) => {
+                    journal::add_transaction(&budget, &transaction)?;
+                    suggestions.record(&transaction);
+                },
+                None => println!("Transaction is discarded.")
+            }
 
             //
             // If multiple transactions requested, then ask if one needs to add another one
@@ -98,64 +158,63 @@ impl CommandInternal for AddTransaction {
         Ok(Parameters { 
             multi: multi, 
             full: full, 
-            category_type: category_type 
+            category_type: category_type,
+            template: Self::get_one_opt(matches, "template"),
+            yes: Self::get_one(matches, "yes")?
         })
     }
 }
 
 
 impl AddTransaction {
-    fn input_transaction(full: bool, accounts: &Vec<Account>, categories: &Vec<Category>) -> Result<Transaction> {
+    fn input_transaction(budget: &binding::Budget, parameters: &Parameters, template: &Template, suggestions: &Suggestions,
+        accounts: &mut Vec<Account>, categories: &mut Vec<Category>) -> Result<Option<Transaction>> 
+    {
         //
-        // Ask for category
+        // Fields filled by template are not requested. Otherwise ask 
+        // for description first: a known one preselects category and
+        // account used with it most often
         //
 
-        let printable_categories: Vec<_> = categories
-            .iter()
-            .map(|category| {
-                format!("{} ({})", category.name, common::category_type_to_string(category.category_type))
-            })
-            .collect();
-
-        let selection = console::select_from_with_prompt(&printable_categories, 
-            "Which category does transaction belong to?")?;
-
-        let category = &categories[selection];
+        let description = match &template.description {
+            Some(description) => description.clone(),
+            None => Self::input_description(suggestions)?
+        };
 
-        //
-        // Ask for account
-        //
+        let category = match template.category.and_then(|id| categories.iter().position(|c| c.id == Some(id))) {
+            Some(category) => category,
+            None => Self::select_category(budget, parameters, categories, suggestions.category_for(&description))?
+        };
 
-        let printable_accounts: Vec<_> = accounts
-            .iter()
-            .map(|account| {
-                format!("{}", account.name)
-            })
-            .collect();
+        let category = &categories[category];
 
-        let selection = console::select_from_with_prompt(&printable_accounts, 
-            "Which account does transaction belong to?")?;
+        let account = match template.account.and_then(|id| accounts.iter().position(|a| a.id == Some(id))) {
+            Some(account) => account,
+            None => Self::select_account(budget, parameters, accounts, suggestions.account_for(&description))?
+        };
 
-        let account = &accounts[selection];
+        let account = &accounts[account];
 
         //
-        // Ask for description, amount and timestamp if necessary and that's it
+        // Ask for amount and timestamp if necessary and that's it
         // Amount will be normalized according to selected category
         //
 
-        let description = console::input_string_with_prompt("Description")?;
-        let amount = console::input_number_with_prompt("Amount (sign will be selected based on category)")?;
+        let amount = match template.amount {
+            Some(amount) => amount,
+            None => Self::input_amount()?
+        };
+
         let amount = common::normalize_amount_by_category(amount, category.category_type);
 
-        let timestamp = if full {
-            let datetime = console::input_string_with_prompt("Enter date and time of the transaction")?;
-            dateparser::parse(&datetime)?
+        let timestamp = if parameters.full {
+            Self::input_timestamp()?
         }
         else {
             Clock::now()
         };
 
-        Ok(Transaction {
+        let transaction = Transaction {
             id: None,
             timestamp: timestamp,
             description: description,
@@ -163,7 +222,165 @@ impl AddTransaction {
             category_id: category.id.unwrap(),
             amount: amount,
             meta_info: MetaInfo::new(Some(Clock::now()), None, None)
-        })
+        };
+
+        if parameters.yes {
+            return Ok(Some(transaction));
+        }
+
+        Self::review_transaction(budget, parameters, transaction, suggestions, accounts, categories)
+    }
+
+    fn review_transaction(budget: &binding::Budget, parameters: &Parameters, mut transaction: Transaction, 
+        suggestions: &Suggestions, accounts: &mut Vec<Account>, categories: &mut Vec<Category>) -> Result<Option<Transaction>>
+    {
+        loop {
+            let fields = {
+                let account = accounts
+                    .iter()
+                    .find(|account| account.id == Some(transaction.account_id))
+                    .unwrap();
+
+                let category = categories
+                    .iter()
+                    .find(|category| category.id == Some(transaction.category_id))
+                    .unwrap();
+
+                [
+                    (Field::Description, "Description", transaction.description.clone()),
+                    (Field::Amount, "Amount", console::colorize_amount(transaction.amount).to_string()),
+                    (Field::Account, "Account", account.name.clone()),
+                    (Field::Category, "Category", 
+                        format!("{} ({})", category.name, common::category_type_to_string(category.category_type))),
+                    (Field::Timestamp, "Date and time", console::format_timestamp(&transaction.timestamp))
+                ]
+            };
+
+            let field = match console::review_with_prompt(&fields, "Save transaction?")? {
+                Review::Save => return Ok(Some(transaction)),
+                Review::Discard => return Ok(None),
+                Review::Edit(field) => field
+            };
+
+            match field {
+                Field::Description => transaction.description = Self::input_description(suggestions)?,
+                Field::Amount => transaction.amount = Self::input_amount()?,
+                Field::Account => {
+                    let account = Self::select_account(budget, parameters, accounts, Some(transaction.account_id))?;
+                    transaction.account_id = accounts[account].id.unwrap();
+                },
+                Field::Category => {
+                    let category = Self::select_category(budget, parameters, categories, Some(transaction.category_id))?;
+                    transaction.category_id = categories[category].id.unwrap();
+                },
+                Field::Timestamp => transaction.timestamp = Self::input_timestamp()?
+            }
+
+            //
+            // Category or amount may be changed, hence sign must be updated
+            //
+
+            let category = categories
+                .iter()
+                .find(|category| category.id == Some(transaction.category_id))
+                .unwrap();
+
+            transaction.amount = common::normalize_amount_by_category(transaction.amount, category.category_type);
+        }
+    }
+
+    fn input_description(suggestions: &Suggestions) -> Result<String> {
+        console::input_string_with_completion("Description", &suggestions.descriptions())
+    }
+
+    fn input_amount() -> Result<isize> {
+        console::input_number_with_prompt("Amount (sign will be selected based on category)")
+    }
+
+    fn input_timestamp() -> Result<Timestamp> {
+        console::input_date_with_prompt("Enter date and time of the transaction")
+    }
+
+    fn select_category(budget: &binding::Budget, parameters: &Parameters, categories: &mut Vec<Category>, 
+        suggested: Option<Id>) -> Result<usize> 
+    {
+        loop {
+            let mut printable_categories: Vec<_> = categories
+                .iter()
+                .map(|category| {
+                    format!("{} ({})", category.name, common::category_type_to_string(category.category_type))
+                })
+                .collect();
+
+            printable_categories.push(NEW_CATEGORY.to_owned());
+
+            let default = categories
+                .iter()
+                .position(|category| category.id == suggested)
+                .unwrap_or(0);
+
+            let selection = console::fuzzy_select_from_with_default(&printable_categories, 
+                "Which category does transaction belong to?", default)?;
+
+            if selection < categories.len() {
+                return Ok(selection);
+            }
+
+            //
+            // The last entry creates a new category, that is
+            // remembered for subsequent transactions. If creation
+            // is discarded, then selection is requested again.
+            // New category must pass the same type filter.
+            //
+
+            if let Some(category) = AddCategory::create_category(budget, parameters.category_type, parameters.yes)? {
+                categories.push(category);
+                return Ok(selection);
+            }
+        }
+    }
+
+    fn select_account(budget: &binding::Budget, parameters: &Parameters, accounts: &mut Vec<Account>, 
+        suggested: Option<Id>) -> Result<usize> 
+    {
+        loop {
+            let mut printable_accounts: Vec<_> = accounts
+                .iter()
+                .map(|account| {
+                    format!("{}", account.name)
+                })
+                .collect();
+
+            printable_accounts.push(NEW_ACCOUNT.to_owned());
+
+            //
+            // Suggested account has priority over the default one
+            //
+
+            let default = accounts
+                .iter()
+                .position(|account| account.id == suggested)
+                .or_else(|| common::default_account_index(accounts))
+                .unwrap_or(0);
+
+            let selection = console::fuzzy_select_from_with_default(&printable_accounts, 
+                "Which account does transaction belong to?", default)?;
+
+            if selection < accounts.len() {
+                return Ok(selection);
+            }
+
+            //
+            // The last entry creates a new account, that is
+            // remembered for subsequent transactions. If creation
+            // is discarded, then selection is requested again
+            //
+
+            if let Some(account) = AddAccount::create_account(budget, parameters.yes)? {
+                accounts.push(account);
+                return Ok(selection);
+            }
+        }
     }
 
     fn needs_another_transaction() -> Result<bool> {
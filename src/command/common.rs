@@ -1,6 +1,8 @@
 use libbdgt::error::{Result, Error};
-use libbdgt::storage::CategoryType;
+use libbdgt::storage::{Account, CategoryType};
 
+use crate::preferences;
+use crate::expression;
 use crate::errors;
 use crate::misc;
 
@@ -44,3 +46,93 @@ pub(crate) fn normalize_amount_by_category(amount: isize, category_type: Categor
         CategoryType::Outcome => -amount.abs()
     }
 }
+
+
+/// Parses an amount of money. Amounts are stored as integers, hence
+/// only integer numbers are accepted.
+/// 
+/// Besides plain numbers, arithmetic expressions of integers with at
+/// least one of `+`, `*`, `/` or parentheses are accepted, e.g. `120/3`.
+/// Result of division is rounded to the nearest integer. Plain
+/// subtraction and separators (`.` and `,`) are not accepted to avoid
+/// confusion with dates, fractions and thousands separators.
+/// 
+/// Returns `None` if the string is not an amount.
+/// 
+/// * `value` - string to parse
+pub(crate) fn parse_amount(value: &str) -> Option<isize> {
+    let body = value.trim_start_matches(['+', '-']);
+
+    if body.contains(['.', ',']) {
+        return None;
+    }
+
+    let is_number = !body.is_empty() && body
+        .chars()
+        .all(|c| c.is_ascii_digit());
+
+    let is_expression = body
+        .chars()
+        .any(|c| matches!(c, '+' | '*' | '/' | '(' | ')'));
+
+    misc::either!(is_number || is_expression
+        => expression::evaluate(value).ok()
+         ; None
+    )
+}
+
+
+/// Checks if a string looks like an amount or an arithmetic
+/// expression, possibly with fractional numbers or thousands
+/// separators, that are not accepted by [`parse_amount`].
+/// 
+/// * `value` - string to check
+pub(crate) fn looks_like_amount(value: &str) -> bool {
+    value.contains(|c: char| c.is_ascii_digit()) && value
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '+' | '-' | '*' | '/' | '(' | ')'))
+}
+
+
+/// Creates an empty table for reports with common format.
+/// 
+/// * `titles` - titles of table columns
+pub(crate) fn create_report_table(titles: prettytable::Row) -> prettytable::Table {
+    use prettytable::format;
+
+    let format = format::FormatBuilder::new()
+        .column_separator('│')
+        .borders('│')
+        .separator(
+            format::LinePosition::Top, 
+            format::LineSeparator::new('─', '┬', '┌', '┐')
+        )
+        .separator(
+            format::LinePosition::Title, 
+            format::LineSeparator::new('─', '┼', '├', '┤')
+        )
+        .separator(
+            format::LinePosition::Bottom, 
+            format::LineSeparator::new('─', '┴', '└', '┘')
+        )
+        .padding(1, 1)
+        .build();
+
+    let mut table = prettytable::Table::new();
+    table.set_format(format);
+    table.set_titles(titles);
+
+    table
+}
+
+
+/// Returns an index of account set as default in preferences (if any).
+/// 
+/// * `accounts` - accounts to look for the default one in
+pub(crate) fn default_account_index(accounts: &[Account]) -> Option<usize> {
+    let name = preferences::current().default_account()?.to_lowercase();
+
+    accounts
+        .iter()
+        .position(|account| account.name.to_lowercase() == name)
+}
@@ -0,0 +1,142 @@
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::preferences::{self, Preferences};
+use crate::error::{Result, Error};
+use crate::console;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Requested configuration action.
+pub(crate) enum Action {
+    /// Print all settings.
+    List,
+
+    /// Print value of a setting.
+    Get(String),
+
+    /// Set value of a setting.
+    Set(String, String),
+
+    /// Reset a setting to default value.
+    Unset(String),
+}
+
+
+/// Preferences management command.
+pub(crate) struct ConfigCommand;
+
+
+impl Command for ConfigCommand {
+    const VERB: &'static str = "config";
+
+    const ABOUT: &'static str = "View and change preferences";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Views and changes preferences of this device.",
+        "Preferences are stored locally and are not synchronized.",
+        "Command line flags and environment variables take precedence over preferences."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .subcommand_required(true)
+            .subcommand(clap::Command::new("list").about("Print all settings with their values"))
+            .subcommand(
+                clap::Command::new("get")
+                    .about("Print value of a setting")
+                    .arg(clap::arg!(<KEY> "name of the setting"))
+            )
+            .subcommand(
+                clap::Command::new("set")
+                    .about("Set value of a setting")
+                    .arg(clap::arg!(<KEY> "name of the setting"))
+                    .arg(clap::arg!(<VALUE> "new value").allow_hyphen_values(true))
+            )
+            .subcommand(
+                clap::Command::new("unset")
+                    .about("Reset a setting to its default value")
+                    .arg(clap::arg!(<KEY> "name of the setting"))
+            )
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let action = Self::parse_args(matches)?;
+        let mut preferences = Preferences::load()?;
+
+        match action {
+            Action::List => Self::print_settings(&preferences)?,
+            Action::Get(key) => println!("{}", preferences.get(&key)?),
+            Action::Set(key, value) => {
+                if key == "default-account" {
+                    Self::ensure_account_exists(&value)?;
+                }
+
+                preferences.set(&key, &value)?;
+                preferences.save()?;
+            },
+            Action::Unset(key) => {
+                preferences.unset(&key)?;
+                preferences.save()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for ConfigCommand {
+    type ParsedArgs = Action;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        match matches.subcommand() {
+            Some(("list", _)) => Ok(Action::List),
+            Some(("get", sub_matches)) => Ok(Action::Get(Self::get_one(sub_matches, "KEY")?)),
+            Some(("set", sub_matches)) => Ok(Action::Set(Self::get_one(sub_matches, "KEY")?, Self::get_one(sub_matches, "VALUE")?)),
+            Some(("unset", sub_matches)) => Ok(Action::Unset(Self::get_one(sub_matches, "KEY")?)),
+            _ => Err(Error::from_message(errors::PARSE_ERROR))
+        }
+    }
+}
+
+
+impl ConfigCommand {
+    fn print_settings(preferences: &Preferences) -> Result<()> {
+        let mut table = common::create_report_table(
+            prettytable::row!["Setting", "Value", "Description"]);
+
+        for setting in preferences::SETTINGS {
+            let value = preferences.get(setting.key)
+                .unwrap_or(setting.default);
+
+            let value = misc::either!(preferences.is_set(setting.key)
+                => value.to_owned()
+                 ; format!("{} (default)", value)
+            );
+
+            let about = misc::either!(setting.values.is_empty()
+                => setting.about.to_owned()
+                 ; format!("{} ({})", setting.about, setting.values.join(", "))
+            );
+
+            table.add_row(prettytable::row![setting.key, value, about]);
+        }
+
+        console::print_paged(&table)
+    }
+
+    fn ensure_account_exists(name: &str) -> Result<()> {
+        let budget = binding::open_budget()?;
+
+        let exists = budget.accounts()?
+            .iter()
+            .any(|account| account.name.to_lowercase() == name.trim().to_lowercase());
+
+        misc::either!(exists
+            => Ok(())
+             ; Err(Error::from_message_with_extra(errors::ACCOUNT_NOT_FOUND, name))
+        )
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::{HashMap, HashSet};
+
+use libbdgt::datetime::{Clock, Timestamp};
+use libbdgt::storage::{Account, Id, Transaction};
+
+use chrono::Datelike;
+use colored::Colorize;
+use itertools::Itertools;
+
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::error::{Result, Error};
+use crate::console::WritePaged;
+use crate::transfers;
+use crate::datetime;
+use crate::binding;
+use crate::console;
+use crate::errors;
+use crate::misc;
+
+
+/// Minimal number of months with a transaction to treat it as recurring.
+const MIN_RECURRING_MONTHS: usize = 2;
+
+
+/// Structure with command parameters.
+pub(crate) struct Parameters {
+    /// Number of months to project balances for.
+    months: i32,
+
+    /// Number of past months used to detect recurring transactions and averages.
+    history: i32,
+
+    /// Balance, that is considered dangerous.
+    threshold: isize,
+
+    /// Print forecast directly without pager.
+    no_pager: bool,
+}
+
+
+/// Transaction, that is expected to repeat every month.
+struct Recurring {
+    /// Transaction description as it was entered last time.
+    description: String,
+
+    /// Expected amount of money.
+    amount: isize,
+
+    /// Day of month, when transaction is expected.
+    day: u32,
+}
+
+
+/// Projected balance of an account.
+struct Projection {
+    /// Balances at the end of each projected month.
+    balances: Vec<isize>,
+
+    /// First date and balance below threshold (if any).
+    first_below: Option<(chrono::NaiveDate, isize)>,
+}
+
+
+/// Balance forecasting command. Projects balances of all accounts into future.
+pub(crate) struct Forecast;
+
+
+impl Command for Forecast {
+    const VERB: &'static str = "forecast";
+
+    const ABOUT: &'static str = "Project balances of accounts for several months ahead";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Projects balance of each account for several months ahead.",
+        "Projection is based on transactions of several previous months:",
+        "\t- transactions, that occur once a month with the same description and category, are",
+        "\t  treated as recurring and are expected on the same day of each following month;",
+        "\t- the rest transactions are averaged per category and spread evenly over each month.",
+        "Transfers made on this device are not analyzed. Fees and transfers made on",
+        "other instances are treated as ordinary income and spendings.",
+        "The first date, when balance is expected to drop below threshold, is reported."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .arg(
+                clap::arg!(-n --months [MONTHS] "number of months to project balances for (the current month included)")
+                    .default_value("3")
+                    .value_parser(clap::value_parser!(i32).range(1..=24))
+            )
+            .arg(
+                clap::arg!(--history [MONTHS] "number of previous months to analyze")
+                    .default_value("3")
+                    .value_parser(clap::value_parser!(i32).range(MIN_RECURRING_MONTHS as i64..=24))
+            )
+            .arg(
+                clap::arg!(-t --threshold [AMOUNT] "balance, that is considered dangerous")
+                    .default_value("0")
+                    .value_parser(clap::value_parser!(isize))
+                    .allow_negative_numbers(true)
+            )
+            .arg(clap::arg!(--"no-pager" "print forecast directly without pager"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let parameters = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+
+        let accounts = budget.accounts()?;
+
+        if accounts.is_empty() {
+            return Err(Error::from_message(errors::NO_ACCOUNTS));
+        }
+
+        //
+        // History consists of complete months only, hence current
+        // month's transactions are not analyzed
+        //
+
+        let now = Clock::now();
+        let local_now = datetime::to_local(&now);
+        let current_month = datetime::make_date(local_now.year(), local_now.month(), 1)?;
+        let history_start = datetime::advance_date(&current_month, datetime::Duration::Month(-parameters.history))?;
+
+        let month_ends = Self::month_ends(&current_month, parameters.months)?;
+
+        //
+        // Project balance of each account
+        //
+
+        let mut table = common::create_report_table(Self::table_titles(&month_ends));
+        let mut recurring_table = common::create_report_table(
+            prettytable::row!["Account", "Description", "Amount", "Day of month"]);
+
+        //
+        // Transfers are not an income or spending, hence they are
+        // excluded the same way as in reports
+        //
+
+        let transfers = transfers::Transfers::link(&budget.transactions_between(history_start, current_month)?,
+            &transfers::Registry::load(&budget)?);
+
+        for account in &accounts {
+            let transactions: Vec<_> = budget.transactions_of_between(account.id.unwrap(), history_start, current_month)?
+                .into_iter()
+                .filter(|transaction| !transfers.is_transfer(transaction))
+                .collect();
+
+            let recurring = Self::detect_recurring(&transactions);
+            let average = Self::monthly_average(&transactions, &recurring, parameters.history);
+            let projection = Self::project(account, &recurring, average, &now, &month_ends, parameters.threshold)?;
+
+            table.add_row(Self::projection_row(account, &projection));
+
+            for item in recurring.values() {
+                recurring_table.add_row(prettytable::Row::new(vec![
+                    prettytable::cell!(account.name),
+                    prettytable::cell!(item.description),
+                    prettytable::cell!(r -> console::colorize_amount(item.amount)),
+                    prettytable::cell!(r -> item.day)
+                ]));
+            }
+        }
+
+        //
+        // Print forecast and detected recurring transactions
+        //
+
+        let mut output = console::Output::new(parameters.no_pager);
+
+        format!("Threshold: {}\n", console::colorize_amount(parameters.threshold))
+            .write_paged(&mut output)?;
+
+        table.write_paged(&mut output)?;
+
+        if !recurring_table.is_empty() {
+            "\nDetected recurring transactions:\n".to_string()
+                .write_paged(&mut output)?;
+
+            recurring_table.write_paged(&mut output)?;
+        }
+
+        output.show()?;
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for Forecast {
+    type ParsedArgs = Parameters;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        Ok(Parameters {
+            months: Self::get_one(matches, "months")?,
+            history: Self::get_one(matches, "history")?,
+            threshold: Self::get_one(matches, "threshold")?,
+            no_pager: Self::get_one(matches, "no-pager")?
+        })
+    }
+}
+
+
+impl Forecast {
+    fn detect_recurring(transactions: &[Transaction]) -> HashMap<(String, Id), Recurring> {
+        //
+        // Transaction is recurring if it occurs at most once a month
+        // in several different months. Transactions are matched by
+        // their description and category.
+        //
+
+        transactions
+            .iter()
+            .sorted_by_key(|transaction| transaction.timestamp)
+            .into_group_map_by(Self::recurring_key)
+            .into_iter()
+            .filter_map(|(key, group)| {
+                let months: HashSet<_> = group
+                    .iter()
+                    .map(|transaction| datetime::to_local(&transaction.timestamp))
+                    .map(|timestamp| (timestamp.year(), timestamp.month()))
+                    .collect();
+
+                if months.len() < MIN_RECURRING_MONTHS || months.len() != group.len() {
+                    return None;
+                }
+
+                let last = group.last().unwrap();
+                let amount = group
+                    .iter()
+                    .map(|transaction| transaction.amount)
+                    .sum::<isize>() / group.len() as isize;
+
+                Some((key, Recurring {
+                    description: last.description.clone(),
+                    amount: amount,
+                    day: datetime::to_local(&last.timestamp).day()
+                }))
+            })
+            .collect()
+    }
+
+    fn monthly_average(transactions: &[Transaction], recurring: &HashMap<(String, Id), Recurring>, history: i32) -> isize {
+        //
+        // Non-recurring transactions are averaged per category
+        //
+
+        transactions
+            .iter()
+            .filter(|transaction| !recurring.contains_key(&Self::recurring_key(transaction)))
+            .into_group_map_by(|transaction| transaction.category_id)
+            .into_values()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|transaction| transaction.amount)
+                    .sum::<isize>() / history as isize
+            })
+            .sum()
+    }
+
+    fn project(account: &Account, recurring: &HashMap<(String, Id), Recurring>, average: isize,
+        now: &Timestamp, month_ends: &[chrono::NaiveDate], threshold: isize) -> Result<Projection>
+    {
+        let mut balance = account.balance as f64;
+        let mut balances = Vec::new();
+        let mut first_below = None;
+
+        let mut date = datetime::to_local(now).date_naive();
+
+        //
+        // Account may be below threshold already
+        //
+
+        if account.balance < threshold {
+            first_below = Some((date, account.balance));
+        }
+
+        for month_end in month_ends {
+            while date < *month_end {
+                date = date.succ_opt()
+                    .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, date.to_string()))?;
+
+                //
+                // Average flow is spread evenly over days of month,
+                // recurring transactions are clamped to month's end
+                //
+
+                let days = datetime::days_in_month(date.year(), date.month())?;
+                balance += average as f64 / days as f64;
+
+                balance += recurring
+                    .values()
+                    .filter(|item| item.day.min(days) == date.day())
+                    .map(|item| item.amount as f64)
+                    .sum::<f64>();
+
+                let rounded = balance.round() as isize;
+                if first_below.is_none() && rounded < threshold {
+                    first_below = Some((date, rounded));
+                }
+            }
+
+            balances.push(balance.round() as isize);
+        }
+
+        Ok(Projection {
+            balances: balances,
+            first_below: first_below
+        })
+    }
+
+    fn month_ends(current_month: &Timestamp, months: i32) -> Result<Vec<chrono::NaiveDate>> {
+        let mut result = Vec::new();
+        for shift in 1..=months {
+            let next_month = datetime::advance_date(current_month, datetime::Duration::Month(shift))?;
+            result.push(datetime::to_local(&(next_month - chrono::Duration::days(1))).date_naive());
+        }
+
+        Ok(result)
+    }
+
+    fn recurring_key(transaction: &&Transaction) -> (String, Id) {
+        (transaction.description.trim().to_lowercase(), transaction.category_id)
+    }
+
+    fn table_titles(month_ends: &[chrono::NaiveDate]) -> prettytable::Row {
+        let mut titles = vec![
+            prettytable::cell!("Account"),
+            prettytable::cell!("Current")
+        ];
+
+        titles.extend(month_ends
+            .iter()
+            .map(|month_end| prettytable::cell!(month_end.format("%B %Y"))));
+
+        titles.push(prettytable::cell!("Below threshold"));
+
+        prettytable::Row::new(titles)
+    }
+
+    fn projection_row(account: &Account, projection: &Projection) -> prettytable::Row {
+        let mut cells = vec![
+            prettytable::cell!(account.name),
+            prettytable::cell!(r -> console::colorize_amount(account.balance))
+        ];
+
+        cells.extend(projection.balances
+            .iter()
+            .map(|balance| prettytable::cell!(r -> console::colorize_amount(*balance))));
+
+        let warning = match projection.first_below {
+            Some((date, balance)) => {
+                let warning = format!("{} ({})", date.format("%d %B %Y"), balance);
+                misc::either!(console::colors_enabled() => warning.red().bold().to_string(); warning)
+            },
+            None => "-".to_string()
+        };
+
+        cells.push(prettytable::cell!(warning));
+
+        prettytable::Row::new(cells)
+    }
+}
@@ -0,0 +1,270 @@
+use libbdgt::datetime::Timestamp;
+use libbdgt::storage::MetaInfo;
+
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::console::WritePaged;
+use crate::journal::Journal;
+use crate::trash::{Item, Trash};
+use crate::error::{Result, Error};
+use crate::console;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Kind of items, that lifecycle can be shown for.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    Account,
+    Category,
+    Plan,
+    Transaction,
+}
+
+
+/// Requested history.
+pub(crate) enum Action {
+    /// Operations performed on this device.
+    Operations,
+
+    /// Lifecycle of an item of specific kind, selected by name or interactively.
+    Item(Kind, Option<String>),
+}
+
+
+/// Item, which lifecycle is displayed.
+struct Lifecycle {
+    /// Printable name of the item.
+    title: String,
+
+    /// Current (or last known) values of the item.
+    details: String,
+
+    /// Time of creation (if known).
+    added: Option<Timestamp>,
+
+    /// Time of the latest change (if known).
+    changed: Option<Timestamp>,
+
+    /// Time of removal (if removed).
+    removed: Option<Timestamp>,
+}
+
+
+/// History command. Prints operations performed on this device or lifecycle of an item.
+pub(crate) struct History;
+
+
+impl Command for History {
+    const VERB: &'static str = "history";
+
+    const ABOUT: &'static str = "Print the latest operations or lifecycle of an item";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Without arguments prints the latest operations performed on this device,",
+        "that can be reverted with 'undo'.",
+        "With a kind of item prints when the item was added, changed and removed.",
+        "Storage keeps times of changes only, so previous values and instances,",
+        "that made changes, are not shown. Removed items are available if they",
+        "were removed on this device."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        let item = |name: &'static str, about: &'static str| {
+            clap::Command::new(name)
+                .about(about)
+                .arg(clap::arg!([NAME] "name of the item (selected interactively if omitted)"))
+        };
+
+        command
+            .subcommand(item("account", "Print lifecycle of an account"))
+            .subcommand(item("category", "Print lifecycle of a category"))
+            .subcommand(item("plan", "Print lifecycle of a plan"))
+            .subcommand(clap::Command::new("transaction").about("Print lifecycle of a selected transaction"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let action = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+        let trash = Trash::load(&budget)?;
+
+        match action {
+            Action::Operations => Self::print_operations(&budget, &trash),
+            Action::Item(kind, name) => {
+                let mut items = Self::current_items(&budget, kind)?;
+                items.extend(Self::removed_items(&trash, kind));
+
+                if items.is_empty() {
+                    return Ok(());
+                }
+
+                let item = match name {
+                    Some(name) => items
+                        .iter()
+                        .find(|item| item.title.to_lowercase() == name.to_lowercase())
+                        .ok_or(Error::from_message_with_extra(errors::ITEM_NOT_FOUND, name))?,
+                    None => {
+                        let printable_items: Vec<_> = items
+                            .iter()
+                            .map(|item| misc::either!(item.removed.is_some()
+                                => format!("{} (removed)", item.title)
+                                 ; item.title.clone()
+                            ))
+                            .collect();
+
+                        &items[console::fuzzy_select_from_with_prompt(&printable_items, "Select item")?]
+                    }
+                };
+
+                Self::print_lifecycle(item)
+            }
+        }
+    }
+}
+
+
+impl CommandInternal for History {
+    type ParsedArgs = Action;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        let kind = match matches.subcommand() {
+            None => return Ok(Action::Operations),
+            Some(("account", _)) => Kind::Account,
+            Some(("category", _)) => Kind::Category,
+            Some(("plan", _)) => Kind::Plan,
+            Some(("transaction", _)) => Kind::Transaction,
+            _ => return Err(Error::from_message(errors::PARSE_ERROR))
+        };
+
+        let name = match kind {
+            Kind::Transaction => None,
+            _ => Self::get_one_opt(matches.subcommand().unwrap().1, "NAME")
+        };
+
+        Ok(Action::Item(kind, name))
+    }
+}
+
+
+impl History {
+    fn print_operations(budget: &binding::Budget, trash: &Trash) -> Result<()> {
+        //
+        // Removals, which snapshots are gone, cannot be reverted,
+        // hence they are not listed (the same way as in `undo`)
+        //
+
+        let mut journal = Journal::load(budget)?;
+        journal.forget_removals(trash);
+
+        //
+        // The latest operation goes first, so its number
+        // is a count of operations to pass to `undo`
+        //
+
+        let mut table = common::create_report_table(
+            prettytable::row!["#", "Time", "Operation"]);
+
+        for (idx, record) in journal.records().iter().rev().enumerate() {
+            table.add_row(prettytable::row![idx + 1, console::format_timestamp(&record.timestamp), record.summary]);
+        }
+
+        console::print_paged(&table)
+    }
+
+    fn print_lifecycle(item: &Lifecycle) -> Result<()> {
+        let events = [("Added", item.added), ("Changed", item.changed), ("Removed", item.removed)];
+
+        let mut table = common::create_report_table(
+            prettytable::row!["Time", "Event"]);
+
+        for (event, timestamp) in events {
+            if let Some(timestamp) = timestamp {
+                table.add_row(prettytable::row![console::format_timestamp(&timestamp), event]);
+            }
+        }
+
+        let mut output = console::Output::new(false);
+
+        format!("{}: {}\n", item.title, item.details).write_paged(&mut output)?;
+        table.write_paged(&mut output)?;
+
+        output.show()
+    }
+
+    fn current_items(budget: &binding::Budget, kind: Kind) -> Result<Vec<Lifecycle>> {
+        let items = match kind {
+            Kind::Account => budget.accounts()?
+                .into_iter()
+                .map(|account| Self::make_lifecycle(account.name,
+                    format!("initial balance {}, balance {}", console::colorize_amount(account.initial_balance),
+                        console::colorize_amount(account.balance)),
+                    &account.meta_info))
+                .collect(),
+            Kind::Category => budget.categories()?
+                .into_iter()
+                .map(|category| Self::make_lifecycle(category.name,
+                    common::category_type_to_string(category.category_type), &category.meta_info))
+                .collect(),
+            Kind::Plan => budget.plans()?
+                .into_iter()
+                .map(|plan| Self::make_lifecycle(plan.name,
+                    format!("limit {}", console::colorize_amount(plan.amount_limit)), &plan.meta_info))
+                .collect(),
+            Kind::Transaction => {
+                let mut transactions = budget.transactions()?;
+                transactions.sort_by_key(|transaction| std::cmp::Reverse(transaction.timestamp));
+
+                transactions
+                    .into_iter()
+                    .map(|transaction| Self::make_lifecycle(
+                        format!("{} ({})", transaction.description, console::format_timestamp(&transaction.timestamp)),
+                        console::colorize_amount(transaction.amount).to_string(), &transaction.meta_info))
+                    .collect()
+            }
+        };
+
+        Ok(items)
+    }
+
+    fn removed_items(trash: &Trash, kind: Kind) -> Vec<Lifecycle> {
+        //
+        // Snapshots keep the time of removal only
+        //
+
+        trash.entries()
+            .iter()
+            .filter_map(|entry| {
+                let (item_kind, title) = match &entry.item {
+                    Item::Account(account) => (Kind::Account, account.name.clone()),
+                    Item::Category(category) => (Kind::Category, category.name.clone()),
+                    Item::Plan(plan) => (Kind::Plan, plan.name.clone()),
+                    Item::Transaction(transaction) => (Kind::Transaction,
+                        format!("{} ({})", transaction.description, console::format_timestamp(&transaction.timestamp)))
+                };
+
+                if item_kind != kind {
+                    return None;
+                }
+
+                Some(Lifecycle {
+                    title: title,
+                    details: Trash::describe(&entry.item),
+                    added: None,
+                    changed: None,
+                    removed: Some(entry.removed)
+                })
+            })
+            .collect()
+    }
+
+    fn make_lifecycle(title: String, details: String, meta_info: &MetaInfo) -> Lifecycle {
+        Lifecycle {
+            title: title,
+            details: details,
+            added: meta_info.added_timestamp,
+            changed: meta_info.changed_timestamp,
+            removed: meta_info.removed_timestamp
+        }
+    }
+}
@@ -6,7 +6,15 @@ mod common;
 //
 mod initialize;
 mod report;
+mod forecast;
+mod search;
 mod add_transaction;
+mod quick_add;
+mod template;
+mod config;
+mod trash;
+mod undo;
+mod history;
 mod transfer;
 mod add_account;
 mod add_category;
@@ -22,7 +30,15 @@ mod about;
 pub(crate) use self::command::Command;
 pub(crate) use self::initialize::Initialize;
 pub(crate) use self::report::Report;
+pub(crate) use self::forecast::Forecast;
+pub(crate) use self::search::Search;
 pub(crate) use self::add_transaction::AddTransaction;
+pub(crate) use self::quick_add::QuickAdd;
+pub(crate) use self::template::TemplateCommand;
+pub(crate) use self::config::ConfigCommand;
+pub(crate) use self::trash::TrashCommand;
+pub(crate) use self::undo::Undo;
+pub(crate) use self::history::History;
 pub(crate) use self::transfer::Transfer;
 pub(crate) use self::add_account::AddAccount;
 pub(crate) use self::add_category::AddCategory;
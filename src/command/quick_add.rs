@@ -0,0 +1,406 @@
+use libbdgt::datetime::{Clock, Timestamp};
+use libbdgt::storage::{Account, Category, MetaInfo, Transaction};
+
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::error::{Result, Error};
+use crate::datetime;
+use crate::console;
+use crate::journal;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Prefix of account tokens.
+const ACCOUNT_PREFIX: char = '@';
+
+/// Prefix of category tokens.
+const CATEGORY_PREFIX: char = '#';
+
+
+/// Transaction fields parsed from a single line.
+#[derive(Default)]
+struct ParsedEntry {
+    /// Words of description.
+    description: Vec<String>,
+
+    /// Amount of money (sign is assigned by category later).
+    amount: Option<isize>,
+
+    /// Index of selected account.
+    account: Option<usize>,
+
+    /// Index of selected category.
+    category: Option<usize>,
+
+    /// Transaction timestamp.
+    timestamp: Option<Timestamp>,
+
+    /// Tokens, that cannot be interpreted.
+    unknown: Vec<String>,
+}
+
+
+/// Quick transaction addition command. Parses a transaction from a single line.
+pub(crate) struct QuickAdd;
+
+
+impl Command for QuickAdd {
+    const VERB: &'static str = "q";
+
+    const ABOUT: &'static str = "Add a transaction from a single line";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Adds a transaction described with a single line, e.g.:",
+        "",
+        "\tbdgt q coffee 4 @cash #food yesterday",
+        "",
+        "Tokens are interpreted as follows:",
+        "\t- @NAME selects an account by its name or a unique name prefix (default account from preferences is used if omitted);",
+        "\t- #NAME selects a category by its name or a unique name prefix;",
+        "\t- the first integer number or expression is an amount (sign is selected based on category);",
+        "\t- 'today', 'yesterday', '-3d', a weekday name or a date select date of the transaction;",
+        "\t- the rest words form a description.",
+        "Missing fields are requested interactively. Transaction is saved after confirmation."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .arg(
+                clap::arg!(<ENTRY> ... "transaction description in quick-entry syntax")
+                    .trailing_var_arg(true)
+                    .allow_hyphen_values(true)
+            )
+            .arg(clap::arg!(-y --yes "save transaction without confirmation"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let (tokens, yes) = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+
+        let accounts = budget.accounts()?;
+        let categories = budget.categories()?;
+
+        //
+        // Parse the line and report all tokens, that cannot be interpreted
+        //
+
+        let entry = Self::parse_entry(&tokens, &accounts, &categories);
+
+        if !entry.unknown.is_empty() {
+            return Err(Error::from_message_with_extra(errors::UNKNOWN_TOKENS, entry.unknown.join(", ")));
+        }
+
+        //
+        // Request missing fields and build a transaction
+        //
+
+        let transaction = Self::complete_entry(entry, &accounts, &categories)?;
+
+        if !yes && !Self::confirm_transaction(&transaction, &accounts, &categories)? {
+            println!("Transaction is discarded.");
+            return Ok(());
+        }
+
+        journal::add_transaction(&budget, &transaction)?;
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for QuickAdd {
+    type ParsedArgs = (Vec<String>, bool);
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        let tokens = matches.get_many::<String>("ENTRY")
+            .ok_or(Error::from_message_with_extra(errors::PARSE_ERROR, "ENTRY"))?
+            .flat_map(|value| value.split_whitespace())
+            .map(str::to_owned)
+            .collect();
+
+        Ok((tokens, Self::get_one(matches, "yes")?))
+    }
+}
+
+
+impl QuickAdd {
+    fn parse_entry(tokens: &[String], accounts: &[Account], categories: &[Category]) -> ParsedEntry {
+        let mut entry = ParsedEntry::default();
+
+        for token in tokens {
+            if let Some(name) = token.strip_prefix(ACCOUNT_PREFIX) {
+                match Self::find_by_name(accounts, name, |account| &account.name) {
+                    Some(idx) if entry.account.is_none() => entry.account = Some(idx),
+                    _ => entry.unknown.push(token.clone())
+                }
+
+                continue;
+            }
+
+            if let Some(name) = token.strip_prefix(CATEGORY_PREFIX) {
+                match Self::find_by_name(categories, name, |category| &category.name) {
+                    Some(idx) if entry.category.is_none() => entry.category = Some(idx),
+                    _ => entry.unknown.push(token.clone())
+                }
+
+                continue;
+            }
+
+            if let Some(amount) = common::parse_amount(token) {
+                match entry.amount {
+                    None => entry.amount = Some(amount),
+                    Some(_) => entry.unknown.push(token.clone())
+                }
+
+                continue;
+            }
+
+            if let Some(timestamp) = Self::parse_date(token) {
+                match entry.timestamp {
+                    None => entry.timestamp = Some(timestamp),
+                    Some(_) => entry.unknown.push(token.clone())
+                }
+
+                continue;
+            }
+
+            //
+            // Fractional amounts and thousands separators are not 
+            // supported, so they are not mistaken for a description
+            //
+
+            if common::looks_like_amount(token) {
+                entry.unknown.push(token.clone());
+                continue;
+            }
+
+            entry.description.push(token.clone());
+        }
+
+        entry
+    }
+
+    fn complete_entry(entry: ParsedEntry, accounts: &[Account], categories: &[Category]) -> Result<Transaction> {
+        let account = match entry.account.or_else(|| common::default_account_index(accounts)) {
+            Some(idx) => &accounts[idx],
+            None => {
+                if accounts.is_empty() {
+                    return Err(Error::from_message(errors::NO_ACCOUNTS));
+                }
+
+                let printable_accounts: Vec<_> = accounts
+                    .iter()
+                    .map(|account| &account.name)
+                    .collect();
+
+                &accounts[console::fuzzy_select_from_with_prompt(&printable_accounts,
+                    "Which account does transaction belong to?")?]
+            }
+        };
+
+        let category = match entry.category {
+            Some(idx) => &categories[idx],
+            None => {
+                if categories.is_empty() {
+                    return Err(Error::from_message(errors::NO_CATEGORIES));
+                }
+
+                let printable_categories: Vec<_> = categories
+                    .iter()
+                    .map(|category| {
+                        format!("{} ({})", category.name, common::category_type_to_string(category.category_type))
+                    })
+                    .collect();
+
+                &categories[console::fuzzy_select_from_with_prompt(&printable_categories,
+                    "Which category does transaction belong to?")?]
+            }
+        };
+
+        let description = if entry.description.is_empty() {
+            console::input_string_with_prompt("Description")?
+        }
+        else {
+            entry.description.join(" ")
+        };
+
+        let amount = match entry.amount {
+            Some(amount) => amount,
+            None => console::input_number_with_prompt("Amount (sign will be selected based on category)")?
+        };
+
+        Ok(Transaction {
+            id: None,
+            timestamp: entry.timestamp.unwrap_or_else(Clock::now),
+            description: description,
+            account_id: account.id.unwrap(),
+            category_id: category.id.unwrap(),
+            amount: common::normalize_amount_by_category(amount, category.category_type),
+            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
+        })
+    }
+
+    fn confirm_transaction(transaction: &Transaction, accounts: &[Account], categories: &[Category]) -> Result<bool> {
+        let account = accounts
+            .iter()
+            .find(|account| account.id == Some(transaction.account_id))
+            .unwrap();
+
+        let category = categories
+            .iter()
+            .find(|category| category.id == Some(transaction.category_id))
+            .unwrap();
+
+        println!(misc::multiline!(
+                "Description: {}",
+                "Amount: {}",
+                "Account: {}",
+                "Category: {} ({})",
+                "Date and time: {}",
+            ),
+            transaction.description,
+            console::colorize_amount(transaction.amount),
+            account.name,
+            category.name, common::category_type_to_string(category.category_type),
+            console::format_timestamp(&transaction.timestamp)
+        );
+
+        console::confirm_with_prompt("Save transaction?", true)
+    }
+
+    fn find_by_name<T, F>(items: &[T], name: &str, name_of: F) -> Option<usize>
+    where
+        F: Fn(&T) -> &String
+    {
+        let name = name.to_lowercase();
+
+        //
+        // Exact match has a priority over a unique prefix
+        //
+
+        if let Some(idx) = items.iter().position(|item| name_of(item).to_lowercase() == name) {
+            return Some(idx);
+        }
+
+        let mut candidates = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| name_of(item).to_lowercase().starts_with(&name));
+
+        match (candidates.next(), candidates.next()) {
+            (Some((idx, _)), None) => Some(idx),
+            _ => None
+        }
+    }
+
+    fn parse_date(token: &str) -> Option<Timestamp> {
+        datetime::parse_date(token, datetime::DateOrder::configured()).ok()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::Datelike;
+    use libbdgt::storage::CategoryType;
+
+    use super::*;
+
+    fn accounts() -> Vec<Account> {
+        ["Cash", "Card"]
+            .iter()
+            .map(|name| Account {
+                id: None,
+                name: name.to_string(),
+                balance: 0,
+                initial_balance: 0,
+                meta_info: MetaInfo::new(None, None, None)
+            })
+            .collect()
+    }
+
+    fn categories() -> Vec<Category> {
+        ["Food", "Fuel", "Salary"]
+            .iter()
+            .map(|name| Category {
+                id: None,
+                name: name.to_string(),
+                category_type: misc::either!(*name == "Salary" => CategoryType::Income; CategoryType::Outcome),
+                meta_info: MetaInfo::new(None, None, None)
+            })
+            .collect()
+    }
+
+    fn parse(line: &str) -> ParsedEntry {
+        let tokens: Vec<_> = line
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect();
+
+        QuickAdd::parse_entry(&tokens, &accounts(), &categories())
+    }
+
+    #[test]
+    fn all_fields_are_recognized() {
+        let entry = parse("hot coffee 4 @cash #food yesterday");
+
+        assert_eq!(entry.description, ["hot", "coffee"]);
+        assert_eq!(entry.amount, Some(4));
+        assert_eq!(entry.account, Some(0));
+        assert_eq!(entry.category, Some(0));
+        assert!(entry.timestamp.is_some());
+        assert!(entry.unknown.is_empty());
+    }
+
+    #[test]
+    fn names_are_matched_by_unique_prefix() {
+        let entry = parse("@car #sal");
+        assert_eq!((entry.account, entry.category), (Some(1), Some(2)));
+
+        let entry = parse("@ca #f");
+        assert_eq!((entry.account, entry.category), (None, None));
+        assert_eq!(entry.unknown, ["@ca", "#f"]);
+    }
+
+    #[test]
+    fn expressions_are_amounts() {
+        assert_eq!(parse("lunch 120/3").amount, Some(40));
+        assert_eq!(parse("lunch (10+5)*2").amount, Some(30));
+    }
+
+    #[test]
+    fn fractions_and_separators_are_rejected() {
+        for token in ["3.50", "1,200", "1.5*2", "-0,5"] {
+            let entry = parse(token);
+
+            assert_eq!(entry.amount, None, "{}", token);
+            assert_eq!(entry.unknown, [token]);
+        }
+    }
+
+    #[test]
+    fn numeric_dates_are_not_amounts() {
+        let entry = parse("05.06.2024");
+        let date = datetime::to_local(&entry.timestamp.unwrap());
+
+        assert_eq!(entry.amount, None);
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 6, 5));
+
+        let entry = parse("05.06");
+        let date = datetime::to_local(&entry.timestamp.unwrap());
+
+        assert_eq!(entry.amount, None);
+        assert_eq!((date.month(), date.day()), (6, 5));
+    }
+
+    #[test]
+    fn repeated_fields_are_unknown() {
+        let entry = parse("10 20 @cash @card today -3d");
+
+        assert_eq!(entry.amount, Some(10));
+        assert_eq!(entry.account, Some(0));
+        assert_eq!(entry.unknown, ["20", "@card", "-3d"]);
+    }
+}
@@ -1,6 +1,5 @@
-use libbdgt::datetime::Clock;
-
 use super::command::{Command, CommandInternal};
+use crate::trash::Trash;
 use crate::error::Result;
 use crate::console;
 use crate::binding;
@@ -35,18 +34,24 @@ impl Command for RemoveAccount {
         let selection = console::select_multiple_from_with_prompt(
             &printable_accounts, "Select accounts to remove")?;
 
+        let mut trash = Trash::load(&budget)?;
+
         for idx in selection {
             let account = &accounts[idx];
             let force = console::confirm_with_prompt(
                 "Remove account with all corresponding transactions?", false)?;
 
-            match budget.remove_account(account.id.unwrap(), force, Clock::now()) {
+            match trash.remove_account(&budget, account, force) {
                 Ok(_) => {},
                 Err(e) => eprintln!("Cannot remove account '{}': {}", account.name, e)
             }
         }
 
-        Ok(())
+        //
+        // Snapshots of removed items allow to restore them later
+        //
+
+        trash.save(&budget)
     }
 }
 
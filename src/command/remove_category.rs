@@ -1,6 +1,5 @@
-use libbdgt::datetime::Clock;
-
 use super::command::{Command, CommandInternal};
+use crate::trash::Trash;
 use super::common;
 use crate::error::Result;
 use crate::console;
@@ -38,15 +37,21 @@ impl Command for RemoveCategory {
         let selection = console::select_multiple_from_with_prompt(
             &printable_categories, "Select categories to remove")?;
 
+        let mut trash = Trash::load(&budget)?;
+
         for idx in selection {
             let category = &categories[idx];
-            match budget.remove_category(category.id.unwrap(), Clock::now()) {
+            match trash.remove_category(&budget, category) {
                 Ok(_) => {},
                 Err(e) => eprintln!("Cannot remove category '{}': {}", category.name, e)
             }
         }
 
-        Ok(())
+        //
+        // Snapshots of removed items allow to restore them later
+        //
+
+        trash.save(&budget)
     }
 }
 
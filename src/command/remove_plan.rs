@@ -1,6 +1,5 @@
-use libbdgt::datetime::Clock;
-
 use super::command::{Command, CommandInternal};
+use crate::trash::Trash;
 use crate::error::Result;
 use crate::console;
 use crate::binding;
@@ -35,15 +34,21 @@ impl Command for RemovePlan {
         let selection = console::select_multiple_from_with_prompt(
             &printable_plans, "Select plans to remove")?;
 
+        let mut trash = Trash::load(&budget)?;
+
         for idx in selection {
             let plan = &plans[idx];
-            match budget.remove_plan(plan.id.unwrap(), Clock::now()) {
+            match trash.remove_plan(&budget, plan) {
                 Ok(_) => {},
                 Err(e) => eprintln!("Cannot remove plan '{}': {}", plan.name, e)
             }
         }
 
-        Ok(())
+        //
+        // Snapshots of removed items allow to restore them later
+        //
+
+        trash.save(&budget)
     }
 }
 
@@ -1,14 +1,19 @@
 use std::collections::HashMap;
 use std::fmt::Write;
 
-use libbdgt::storage::{Account, Plan, Id, Category};
-use libbdgt::datetime::Timestamp;
+use libbdgt::storage::{Account, Plan, Id, Category, Transaction};
+use libbdgt::datetime::{Clock, Timestamp};
 
+use chrono::Datelike;
 use itertools::Itertools;
 
 use super::command::{Command, CommandInternal};
+use super::common;
 use crate::error::{Result, Error};
 use crate::console::WritePaged;
+use crate::chart::{self, BarChart, ChartRow, ProgressChart, ProgressRow};
+use crate::transfers;
+use crate::preferences;
 use crate::datetime;
 use crate::binding;
 use crate::console;
@@ -20,12 +25,9 @@ use crate::misc;
 type Interval = (Timestamp, Timestamp);
 
 
-/// Type of table, that contains a report data.
-type ReportTable = prettytable::Table;
-
-
-/// Type of report, that will be printed.
-type PrintableReport = (String, ReportTable);
+/// Type of report, that will be printed. Report body is 
+/// either a table or a chart.
+type PrintableReport = (String, Box<dyn WritePaged>);
 
 
 /// Target for a report.
@@ -38,6 +40,12 @@ enum ReportTarget {
 
     /// Report is built for a plan. If none specified, all plans are used.
     Plan(Option<Id>),
+
+    /// Report shows income and outcome month by month.
+    CashFlow,
+
+    /// Report shows transfers between accounts.
+    Transfers,
 }
 
 
@@ -52,8 +60,26 @@ pub(crate) struct Parameters {
     /// Month to build report for.
     month: i32,
 
+    /// Week to build report for (if any).
+    week: Option<i32>,
+
+    /// Quarter to build report for (if any).
+    quarter: Option<i32>,
+
     /// Report target
     target: ReportTarget,
+
+    /// Print report directly without pager.
+    no_pager: bool,
+
+    /// Display report as a chart instead of a table.
+    chart: bool,
+
+    /// Display the latest transactions first.
+    descending: bool,
+
+    /// Include transfers into income and outcome totals.
+    with_transfers: bool,
 }
 
 
@@ -66,6 +92,14 @@ impl Command for Report {
 
     const ABOUT: &'static str = "Build a report";
 
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Builds a report for accounts, categories, plans, cash flow or transfers.",
+        "",
+        "Transfers are linked and excluded from income and outcome totals only if",
+        "they were made on this device. Transfers made on other instances (or before",
+        "transfers were recorded) are displayed as ordinary income and spendings."
+    );
+
     fn add_args(command: clap::Command) -> clap::Command {
         command
             .arg(
@@ -76,48 +110,114 @@ impl Command for Report {
                 clap::arg!(-m --month [MONTH] "month to build report for (defaults to current month)")
                     .conflicts_with("epoch")
                     .default_value("0")
-                    .value_parser(clap::value_parser!(i32).range(-1..=12))
+                    .value_parser(clap::value_parser!(i32).range(..=12))
                     .allow_negative_numbers(true)
                     .long_help(misc::multiline!(
-                        "Possible values for MONTH parameter: [-1 .. 12].",
+                        "Possible values for MONTH parameter: [.. 12].",
                         " - Positive values denote number of month in year",
                         " - Zero denotes the current month",
-                        " - Negative one denotes the previous month",
+                        " - Negative value -n denotes the n-th previous month",
                     ))
             )
             .arg(
                 clap::arg!(-y --year [YEAR] "year to build report for (defaults to current year)")
                     .conflicts_with("epoch")
                     .default_value("0")
-                    .value_parser(clap::value_parser!(i32).range(-1..))
+                    .value_parser(clap::value_parser!(i32))
                     .allow_negative_numbers(true)
                     .long_help(misc::multiline!(
-                        "Possible values for YEAR parameter: -1, 0 or just a year.",
+                        "Possible values for YEAR parameter: a non-positive number or just a year.",
                         " - Zero denotes the current year",
-                        " - Negative one denotes the previous year",
+                        " - Negative value -n denotes the n-th previous year",
+                    ))
+            )
+            .arg(
+                clap::arg!(-w --week [WEEK] "week to build report for (defaults to current week)")
+                    .conflicts_with_all(["epoch", "month", "quarter"])
+                    .default_missing_value("0")
+                    .value_parser(clap::value_parser!(i32).range(..=53))
+                    .allow_negative_numbers(true)
+                    .long_help(misc::multiline!(
+                        "Possible values for WEEK parameter: [.. 53].",
+                        " - Positive values denote ISO week number in year",
+                        " - Zero denotes the current week",
+                        " - Negative value -n denotes the n-th previous week",
+                        "Relative weeks start according to 'week-start' preference.",
+                    ))
+            )
+            .arg(
+                clap::arg!(-q --quarter [QUARTER] "quarter to build report for (defaults to current quarter)")
+                    .conflicts_with_all(["epoch", "month", "week"])
+                    .default_missing_value("0")
+                    .value_parser(clap::value_parser!(i32).range(..=4))
+                    .allow_negative_numbers(true)
+                    .long_help(misc::multiline!(
+                        "Possible values for QUARTER parameter: [.. 4].",
+                        " - Positive values denote number of quarter in year",
+                        " - Zero denotes the current quarter",
+                        " - Negative value -n denotes the n-th previous quarter",
                     ))
             )
             .arg(
                 clap::arg!(-a --account <ACCOUNT> "build report for specified account")
                     .value_parser(clap::value_parser!(usize))
-                    .conflicts_with_all(["accounts", "categories", "plan", "plans"])
+                    .conflicts_with_all(["accounts", "categories", "plan", "plans", "cash-flow", "transfers"])
             )
             .arg(
-                clap::arg!(--accounts "build report for all accounts (this is default option)")
-                    .conflicts_with_all(["account", "categories", "plan", "plans"])
+                clap::arg!(--accounts "build report for all accounts (this is default option unless configured)")
+                    .conflicts_with_all(["account", "categories", "plan", "plans", "cash-flow", "transfers"])
             )
             .arg(
                 clap::arg!(--categories "build report for all categories")
-                    .conflicts_with_all(["account", "accounts", "plan", "plans"])
+                    .conflicts_with_all(["account", "accounts", "plan", "plans", "cash-flow", "transfers"])
             )
             .arg(
                 clap::arg!(-p --plan <PLAN> "build report for specified plan")
                     .value_parser(clap::value_parser!(usize))
-                    .conflicts_with_all(["accounts", "categories", "plans"])
+                    .conflicts_with_all(["accounts", "categories", "plans", "cash-flow", "transfers"])
             )
             .arg(
                 clap::arg!(--plans "build report for all plans")
-                    .conflicts_with_all(["account", "categories", "plan"])
+                    .conflicts_with_all(["account", "categories", "plan", "cash-flow", "transfers"])
+            )
+            .arg(
+                clap::arg!(--"cash-flow" "build month by month report of income and outcome")
+                    .conflicts_with_all(["account", "accounts", "categories", "plan", "plans", "transfers"])
+            )
+            .arg(
+                clap::arg!(--transfers "build report for all transfers between accounts")
+                    .conflicts_with_all(["account", "accounts", "categories", "plan", "plans", "cash-flow"])
+            )
+            .arg(
+                clap::arg!(--"with-transfers" "include transfers into income and outcome totals")
+                    .conflicts_with_all(["account", "accounts", "plan", "plans", "transfers"])
+            )
+            .arg(
+                clap::arg!(--order [ORDER] "order of transactions in account reports")
+                    .value_parser(["asc", "desc"])
+                    .default_value("asc")
+            )
+            .arg(
+                clap::arg!(--chart "display report as a chart")
+                    .conflicts_with_all(["account", "accounts", "transfers"])
+                    .long_help(misc::multiline!(
+                        "Display report as a chart scaled to terminal width.",
+                        "Charts are available for categories, plans and cash flow reports:",
+                        " - categories are displayed as bars with monthly trends",
+                        " - plans are displayed as progress bars of spent amount",
+                        " - cash flow is displayed as bars of monthly balance change",
+                    ))
+            )
+            .arg(
+                clap::arg!(--"no-pager" "print report directly without pager")
+                    .long_help(misc::multiline!(
+                        "Print report directly into STDOUT without pager.",
+                        "By default pager is used only if STDOUT is a terminal.",
+                        "This can also be controlled with BDGT_PAGER environment variable:",
+                        " - 'always' forces pager usage",
+                        " - 'never' disables pager",
+                        " - 'auto' (or any other value) selects pager automatically",
+                    ))
             )
     }
 
@@ -132,30 +232,44 @@ impl Command for Report {
 
         let reports = match parameters.target {
             ReportTarget::Account(account) => {
-                Self::build_accounts_report(budget, interval, account)?
+                Self::build_accounts_report(budget, interval, account, parameters.descending)?
             },
             ReportTarget::Category(_) => {
-                Self::build_categories_report(budget, interval)?
+                Self::build_categories_report(budget, interval, parameters.chart, parameters.with_transfers)?
             },
             ReportTarget::Plan(plan) => {
-                Self::build_plans_report(budget, interval, plan)?
+                Self::build_plans_report(budget, interval, plan, parameters.chart)?
+            },
+            ReportTarget::CashFlow => {
+                Self::build_cash_flow_report(budget, interval, parameters.chart, parameters.with_transfers)?
+            },
+            ReportTarget::Transfers => {
+                Self::build_transfers_report(budget, interval)?
             },
         };
 
         //
-        // Print all reports using pager
+        // Print all reports using pager (if allowed)
         //
 
-        let mut pager = minus::Pager::new();
+        let mut output = console::Output::new(parameters.no_pager);
+
+        if let (Some(_), Some((start, end))) = (parameters.week, interval) {
+            let last_day = datetime::advance_date(&end, datetime::Duration::Day(-1))?;
+            let (year, week) = datetime::iso_week(&last_day);
+
+            output.write_str(&format!("ISO week {} of {}: {} – {}\n\n", week, year,
+                console::format_timestamp(&start), console::format_timestamp(&last_day)))?;
+        }
 
         for (preamble, table) in reports {
-            preamble.write_paged(&mut pager)?;
-            table.write_paged(&mut pager)?;
+            preamble.write_paged(&mut output)?;
+            table.write_paged(&mut output)?;
 
-            pager.write_str("\n")?;
+            output.write_str("\n")?;
         }
 
-        minus::page_all(pager)?;
+        output.show()?;
 
         Ok(())
     }
@@ -169,14 +283,36 @@ impl CommandInternal for Report {
         let epoch = Self::get_one(matches, "epoch")?;
         let month = Self::get_one(matches, "month")?;
         let year = Self::get_one(matches, "year")?;
+        let week = Self::get_one_opt(matches, "week");
+        let quarter = Self::get_one_opt(matches, "quarter");
+
+        let no_pager = Self::get_one(matches, "no-pager")?;
+        let chart = Self::get_one(matches, "chart")?;
+        let descending = Self::get_one::<String>(matches, "order")? == "desc";
+        let with_transfers = Self::get_one(matches, "with-transfers")?;
 
         let target = Self::get_target(matches)?;
 
+        //
+        // Default target comes from preferences, hence it is
+        // not checked for conflicts by argument parser
+        //
+
+        if chart && matches!(target, ReportTarget::Account(_) | ReportTarget::Transfers) {
+            return Err(Error::from_message(errors::CHART_NOT_AVAILABLE));
+        }
+
         Ok(Parameters { 
             epoch: epoch, 
             year: year, 
             month: month,
-            target: target
+            week: week,
+            quarter: quarter,
+            target: target,
+            no_pager: no_pager,
+            chart: chart,
+            descending: descending,
+            with_transfers: with_transfers
         })
     }
 }
@@ -204,17 +340,32 @@ impl Report {
             return Ok(ReportTarget::Plan(plan));
         }
 
+        if Self::get_one(matches, "cash-flow")? {
+            return Ok(ReportTarget::CashFlow);
+        }
+
+        if Self::get_one(matches, "transfers")? {
+            return Ok(ReportTarget::Transfers);
+        }
+
         //
-        // By default, report is built for all accounts
+        // By default, report is built for a target from preferences
+        // (all accounts unless configured otherwise)
         //
 
-        Ok(ReportTarget::Account(None))
+        Ok(match preferences::current().report_target() {
+            "categories" => ReportTarget::Category(()),
+            "plans" => ReportTarget::Plan(None),
+            "cash-flow" => ReportTarget::CashFlow,
+            "transfers" => ReportTarget::Transfers,
+            _ => ReportTarget::Account(None)
+        })
     }
 }
 
 
 impl Report {
-    fn build_accounts_report(budget: binding::Budget, interval: Option<Interval>, account: Option<Id>) -> Result<Vec<PrintableReport>> {
+    fn build_accounts_report(budget: binding::Budget, interval: Option<Interval>, account: Option<Id>, descending: bool) -> Result<Vec<PrintableReport>> {
         //
         // Query for account(s) data
         //
@@ -234,70 +385,126 @@ impl Report {
             .map(|category| (category.id.unwrap(), category))
             .collect();
 
+        //
+        // Transfers are displayed with counterpart account names,
+        // hence transactions and names of all accounts are necessary
+        //
+
+        let transfers = transfers::Transfers::link(&Self::query_transactions(&budget, &interval)?,
+            &transfers::Registry::load(&budget)?);
+        let names = Self::account_names(&budget)?;
+
         //
         // Let's build reports here!
         //
 
         let mut reports = Vec::new();
         for account in accounts {
-            reports.push(Self::internal_build_account_report(&budget, &interval, &account, &categories)?)
+            reports.push(Self::internal_build_account_report(&budget, &interval, &account, &categories, &transfers, &names, descending)?)
         }
 
         Ok(reports)
     }
 
-    fn internal_build_account_report(budget: &binding::Budget, interval: &Option<Interval>, account: &Account, categories: &HashMap<Id, Category>) -> Result<PrintableReport> {
-        let preamble = format!("Account: {}\nIdentifier: {}\nCurrent balance: {}\n",
-            account.name, uuid::Uuid::from_bytes(account.id.unwrap()), console::colorize_amount(account.balance));
-        
+    fn internal_build_account_report(budget: &binding::Budget, interval: &Option<Interval>, account: &Account, categories: &HashMap<Id, Category>,
+        transfers: &transfers::Transfers, names: &HashMap<Id, String>, descending: bool) -> Result<PrintableReport>
+    {
         //
-        // Query for transactions, that correspond to the account
+        // Query for all transactions, that correspond to the account.
+        // Earlier transactions are necessary to compute opening balance.
         //
 
-        let transactions = match interval {
-            Some((start_timestamp, end_timestamp)) => {
-                budget.transactions_of_between(account.id.unwrap(), *start_timestamp, *end_timestamp)?
-            },
-            None => {
-                budget.transactions_of(account.id.unwrap())?
-            }
-        };
+        let transactions: Vec<_> = budget.transactions_of(account.id.unwrap())?
+            .into_iter()
+            .sorted_by_key(|transaction| transaction.timestamp)
+            .collect();
+
+        let (earlier, transactions): (Vec<_>, Vec<_>) = transactions
+            .into_iter()
+            .filter(|transaction| {
+                interval.is_none_or(|(_, end_timestamp)| transaction.timestamp < end_timestamp)
+            })
+            .partition(|transaction| {
+                interval.is_some_and(|(start_timestamp, _)| transaction.timestamp < start_timestamp)
+            });
+
+        let opening_balance = earlier
+            .iter()
+            .fold(account.initial_balance, |accumulator, transaction| accumulator + transaction.amount);
 
         //
-        // Now let's build a report
+        // Compute running balance after each transaction
         //
 
-        let mut table = Self::create_report_table(
-            prettytable::row!["Description", "Amount", "Date and time", "Category"]);
+        let mut rows = Vec::new();
+        let mut balance = opening_balance;
 
         for transaction in transactions {
+            balance += transaction.amount;
+            rows.push((transaction, balance));
+        }
+
+        let closing_balance = balance;
+
+        if descending {
+            rows.reverse();
+        }
+
+        let preamble = format!(misc::multiline!(
+                "Account: {}",
+                "Identifier: {}",
+                "Current balance: {}",
+                "Opening balance: {}",
+                "Closing balance: {}",
+                ""
+            ),
+            account.name, uuid::Uuid::from_bytes(account.id.unwrap()), 
+            console::colorize_amount(account.balance),
+            console::colorize_amount(opening_balance),
+            console::colorize_amount(closing_balance)
+        );
+
+        //
+        // Now let's build a report
+        //
+
+        let mut table = common::create_report_table(
+            prettytable::row!["Description", "Amount", "Balance", "Date and time", "Category"]);
+
+        for (transaction, balance) in rows {
+            //
+            // Transfers are displayed with a counterpart account instead of category
+            //
+
+            let category = match transfers.leg(&transaction) {
+                Some(leg) if leg.role != transfers::Role::Fee => {
+                    let arrow = misc::either!(leg.role == transfers::Role::Outgoing => "→"; "←");
+                    format!("{} {}", arrow, names.get(&leg.counterpart).map_or("?", String::as_str))
+                },
+                _ => categories.get(&transaction.category_id).unwrap().name.clone()
+            };
+
             table.add_row(prettytable::Row::new(vec![
                 prettytable::cell!(transaction.description),
                 prettytable::cell!(r -> console::colorize_amount(transaction.amount)),
-                prettytable::cell!(transaction.timestamp.to_rfc2822()),
-                prettytable::cell!(categories.get(&transaction.category_id).unwrap().name)
+                prettytable::cell!(r -> console::colorize_amount(balance)),
+                prettytable::cell!(console::format_timestamp(&transaction.timestamp)),
+                prettytable::cell!(category)
             ]));
         }
 
-        Ok((preamble, table))
+        Ok((preamble, Box::new(table)))
     }
 }
 
 
 impl Report {
-    fn build_categories_report(budget: binding::Budget, interval: Option<Interval>) -> Result<Vec<PrintableReport>> {
+    fn build_categories_report(budget: binding::Budget, interval: Option<Interval>, chart: bool, with_transfers: bool) -> Result<Vec<PrintableReport>> {
         //
         // Query transactions
         //
 
-        let transactions = match interval {
-            Some((start_timestamp, end_timestamp)) => {
-                budget.transactions_between(start_timestamp, end_timestamp)?
-            },
-            None => {
-                budget.transactions()?
-            }
-        };
+        let transactions = Self::query_transactions_for_totals(&budget, &interval, with_transfers)?;
 
         if transactions.is_empty() {
             return Ok(Vec::new());
@@ -313,6 +520,15 @@ impl Report {
             .map(|category| (category.id.unwrap(), category))
             .collect();
 
+        //
+        // Months are necessary for trends in charts only
+        //
+
+        let months = misc::either!(chart
+            => Self::months_of(&interval, &transactions)?
+             ; Vec::new()
+        );
+
         //
         // Now let's build a report
         //
@@ -324,35 +540,57 @@ impl Report {
             .into_iter()
             .map(|(category, group)| {
                 let category = categories.get(&category).unwrap();
+                let group: Vec<_> = group.collect();
+
                 let total = group
+                    .iter()
                     .fold(0isize, |accumulator, transaction| accumulator + transaction.amount);
 
-                (category.category_type, category.name.to_owned(), total)
+                let trend = Self::monthly_totals(group.iter(), &months);
+
+                (category.category_type, category.name.to_owned(), total, trend)
             })
-            .sorted_by_key(|(category_type, _, total)| (*category_type, *total))
+            .sorted_by_key(|(category_type, _, total, _)| (*category_type, *total))
             .collect();
 
         //
-        // Put all data into a table
+        // Put all data into a chart...
+        //
+
+        if chart {
+            let rows = report
+                .into_iter()
+                .map(|(_, name, total, trend)| ChartRow {
+                    label: name,
+                    value: total,
+                    trend: misc::either!(trend.len() > 1 => Some(chart::sparkline(&trend)); None)
+                })
+                .collect();
+
+            return Ok(vec![("".to_string(), Box::new(BarChart::new(rows)))]);
+        }
+
+        //
+        // ...or into a table
         //
 
-        let mut table = Self::create_report_table(
+        let mut table = common::create_report_table(
             prettytable::row!["Category", "Total amount"]);
 
-        for (_, name, total) in report {
+        for (_, name, total, _) in report {
             table.add_row(prettytable::Row::new(vec![
                 prettytable::cell!(name),
                 prettytable::cell!(r -> console::colorize_amount(total))
             ]));
         }
 
-        Ok(vec![("".to_string(), table)])
+        Ok(vec![("".to_string(), Box::new(table))])
     }
 }
 
 
 impl Report {
-    fn build_plans_report(budget: binding::Budget, interval: Option<Interval>, plan: Option<Id>) -> Result<Vec<PrintableReport>> {
+    fn build_plans_report(budget: binding::Budget, interval: Option<Interval>, plan: Option<Id>, chart: bool) -> Result<Vec<PrintableReport>> {
         //
         // Query for plan(s) data
         //
@@ -362,11 +600,30 @@ impl Report {
             None => budget.plans()?
         };
 
+        //
+        // Chart contains progress bars of spent amount
+        //
+
+        if chart {
+            let mut rows = Vec::new();
+            for plan in plans {
+                let (category, spent) = Self::internal_build_plan_report(&budget, &interval, &plan)?;
+
+                rows.push(ProgressRow {
+                    label: format!("{} ({})", plan.name, category.name),
+                    current: spent,
+                    limit: plan.amount_limit
+                });
+            }
+
+            return Ok(vec![("".to_string(), Box::new(ProgressChart::new(rows)))]);
+        }
+
         //
         // Now we are ready to build report (it is summarized )
         //
 
-        let mut table = Self::create_report_table(
+        let mut table = common::create_report_table(
             prettytable::row!["Plan", "Category", "Spent", "Remains"]);
 
         for plan in plans {
@@ -380,7 +637,7 @@ impl Report {
             ]));
         }
 
-        Ok(vec![("".to_string(), table)])
+        Ok(vec![("".to_string(), Box::new(table))])
     }
 
     fn internal_build_plan_report(budget: &binding::Budget, interval: &Option<Interval>, plan: &Plan) -> Result<(Category, isize)> {
@@ -413,11 +670,223 @@ impl Report {
 
 
 impl Report {
+    fn build_cash_flow_report(budget: binding::Budget, interval: Option<Interval>, chart: bool, with_transfers: bool) -> Result<Vec<PrintableReport>> {
+        //
+        // Query transactions and split them into months
+        //
+
+        let transactions = Self::query_transactions_for_totals(&budget, &interval, with_transfers)?;
+
+        if transactions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let months = Self::months_of(&interval, &transactions)?;
+
+        let (income, outcome): (Vec<_>, Vec<_>) = transactions
+            .iter()
+            .partition(|transaction| transaction.amount > 0);
+
+        let income = Self::monthly_totals(income.into_iter(), &months);
+        let outcome = Self::monthly_totals(outcome.into_iter(), &months);
+
+        //
+        // Chart contains bars of monthly balance change and
+        // sparklines with trends in preamble
+        //
+
+        if chart {
+            let net: Vec<_> = income
+                .iter()
+                .zip(outcome.iter())
+                .map(|(income, outcome)| income + outcome)
+                .collect();
+
+            let preamble = format!("Income:  {}\nOutcome: {}\nNet:     {}\n",
+                chart::sparkline(&income), chart::sparkline(&outcome), chart::sparkline(&net));
+
+            let rows = months
+                .iter()
+                .zip(net)
+                .map(|(month, net)| ChartRow {
+                    label: datetime::to_local(month).format("%B %Y").to_string(),
+                    value: net,
+                    trend: None
+                })
+                .collect();
+
+            return Ok(vec![(preamble, Box::new(BarChart::new(rows)))]);
+        }
+
+        //
+        // Put all data into a table
+        //
+
+        let mut table = common::create_report_table(
+            prettytable::row!["Month", "Income", "Outcome", "Net"]);
+
+        for ((month, income), outcome) in months.iter().zip(income).zip(outcome) {
+            table.add_row(prettytable::Row::new(vec![
+                prettytable::cell!(datetime::to_local(month).format("%B %Y")),
+                prettytable::cell!(r -> console::colorize_amount(income)),
+                prettytable::cell!(r -> console::colorize_amount(outcome)),
+                prettytable::cell!(r -> console::colorize_amount(income + outcome))
+            ]));
+        }
+
+        Ok(vec![("".to_string(), Box::new(table))])
+    }
+}
+
+
+impl Report {
+    fn build_transfers_report(budget: binding::Budget, interval: Option<Interval>) -> Result<Vec<PrintableReport>> {
+        //
+        // Restore transfers from transactions of all accounts
+        //
+
+        let transfers = transfers::Transfers::link(&Self::query_transactions(&budget, &interval)?,
+            &transfers::Registry::load(&budget)?);
+        let names = Self::account_names(&budget)?;
+
+        let name_of = |account: &Id| names.get(account).cloned().unwrap_or_default();
+
+        //
+        // Now let's build a report
+        //
+
+        let mut table = common::create_report_table(
+            prettytable::row!["Date and time", "From", "To", "Sent", "Received", "Fee"]);
+
+        for transfer in transfers.transfers() {
+            table.add_row(prettytable::Row::new(vec![
+                prettytable::cell!(console::format_timestamp(&transfer.timestamp)),
+                prettytable::cell!(name_of(&transfer.from)),
+                prettytable::cell!(name_of(&transfer.to)),
+                prettytable::cell!(r -> transfer.sent),
+                prettytable::cell!(r -> transfer.received),
+                prettytable::cell!(r -> transfer.fee)
+            ]));
+        }
+
+        Ok(vec![("".to_string(), Box::new(table))])
+    }
+}
+
+
+impl Report {
+    fn query_transactions_for_totals(budget: &binding::Budget, interval: &Option<Interval>, with_transfers: bool) -> Result<Vec<Transaction>> {
+        let transactions = Self::query_transactions(budget, interval)?;
+
+        if with_transfers {
+            return Ok(transactions);
+        }
+
+        //
+        // Transfers do not change total amount of money, hence 
+        // they are not an income or spending
+        //
+
+        let transfers = transfers::Transfers::link(&transactions, &transfers::Registry::load(budget)?);
+
+        Ok(transactions
+            .into_iter()
+            .filter(|transaction| !transfers.is_transfer(transaction))
+            .collect())
+    }
+
+    fn account_names(budget: &binding::Budget) -> Result<HashMap<Id, String>> {
+        let names = budget.accounts()?
+            .into_iter()
+            .map(|account| (account.id.unwrap(), account.name))
+            .collect();
+
+        Ok(names)
+    }
+
+    fn query_transactions(budget: &binding::Budget, interval: &Option<Interval>) -> Result<Vec<Transaction>> {
+        let transactions = match interval {
+            Some((start_timestamp, end_timestamp)) => {
+                budget.transactions_between(*start_timestamp, *end_timestamp)?
+            },
+            None => {
+                budget.transactions()?
+            }
+        };
+
+        Ok(transactions)
+    }
+
+    fn months_of(interval: &Option<Interval>, transactions: &[Transaction]) -> Result<Vec<Timestamp>> {
+        //
+        // If no interval specified, then months of the first and
+        // the last transactions are used as bounds
+        //
+
+        let (first, end) = match interval {
+            Some((start_timestamp, end_timestamp)) => (*start_timestamp, *end_timestamp),
+            None => {
+                let bounds = transactions
+                    .iter()
+                    .map(|transaction| transaction.timestamp)
+                    .minmax()
+                    .into_option();
+
+                match bounds {
+                    Some((first, last)) => (first, last + chrono::Duration::seconds(1)),
+                    None => return Ok(Vec::new())
+                }
+            }
+        };
+
+        let mut months = Vec::new();
+        let first = datetime::to_local(&first);
+        let mut month = datetime::make_date(first.year(), first.month(), 1)?;
+
+        while month < end {
+            months.push(month);
+            month = datetime::advance_date(&month, datetime::Duration::Month(1))?;
+        }
+
+        Ok(months)
+    }
+
+    fn monthly_totals<'a, I>(transactions: I, months: &[Timestamp]) -> Vec<isize>
+    where
+        I: Iterator<Item = &'a Transaction>
+    {
+        let indices: HashMap<_, _> = months
+            .iter()
+            .enumerate()
+            .map(|(idx, month)| (idx, datetime::to_local(month)))
+            .map(|(idx, month)| ((month.year(), month.month()), idx))
+            .collect();
+
+        let mut totals = vec![0isize; months.len()];
+        for transaction in transactions {
+            let timestamp = datetime::to_local(&transaction.timestamp);
+            let key = (timestamp.year(), timestamp.month());
+            if let Some(idx) = indices.get(&key) {
+                totals[*idx] += transaction.amount;
+            }
+        }
+
+        totals
+    }
+
     fn time_interval(parameters: &Parameters) -> Result<Option<Interval>> {
         if parameters.epoch {
             return Ok(None);
         }
 
+        if let Some(week) = parameters.week {
+            return Self::week_interval(week, parameters.year).map(Some);
+        }
+
+        if let Some(quarter) = parameters.quarter {
+            return Self::quarter_interval(quarter, parameters.year).map(Some);
+        }
+
         //
         // Time interval parameters are parsed according to the 
         // following table:
@@ -425,15 +894,15 @@ impl Report {
         // +------+------+--------+------------------------------------------+
         // | Case | Year |  Month | Result                                   |
         // +------+------+--------+------------------------------------------+
-        // |    1 |   -1 | 1 - 12 | Report for specific month, last year     |
-        // |    2 |   -1 |      0 | Report for last year                     |
-        // |    3 |   -1 |     -1 | Not supported                            |
+        // |    1 |  < 0 | 1 - 12 | Report for specific month, past year     |
+        // |    2 |  < 0 |      0 | Report for past year                     |
+        // |    3 |  < 0 |    < 0 | Not supported                            |
         // |    4 |    0 | 1 - 12 | Report for specific month, current year  |
         // |    5 |    0 |      0 | Report for current month                 |
-        // |    6 |    0 |     -1 | Report for previous month                |
+        // |    6 |    0 |    < 0 | Report for previous month (any year)     |
         // |    7 |  any | 1 - 12 | Report for specific month, specific year |
         // |    8 |  any |      0 | Report for specific year                 |
-        // |    9 |  any |     -1 | Not supported                            |
+        // |    9 |  any |    < 0 | Not supported                            |
         // +------+------+--------+------------------------------------------+
         //
         // Report for current year can be obtained by providing the 
@@ -442,7 +911,7 @@ impl Report {
 
         let (month, year) = (parameters.month, parameters.year);
         
-        if month == -1 && year != 0 {
+        if month < 0 && year != 0 {
             //
             // Cases 3 and 9
             //
@@ -450,58 +919,127 @@ impl Report {
             return Err(Error::from_message(errors::INVALID_INTERVAL));
         }
 
-        let duration = match (month, year) {
+        let ((year, month), duration) = match (month, year) {
             (0, y) if y != 0 => {
                 //
-                // Case 2 and case 8
+                // Case 2 and case 8: report covers the whole calendar year
                 //
 
-                datetime::Duration::Year(1)
+                ((datetime::absolute_year(y), 1), datetime::Duration::Year(1))
             },
             _ => {
                 //
                 // Rest cases
                 //
 
-                datetime::Duration::Month(1)
+                (datetime::absolute_year_month(year, month), datetime::Duration::Month(1))
             }
         };
 
-        let start = datetime::make_date(
-            datetime::absolute_year(year), 
-            datetime::absolute_month(month), 
-            1)?;
+        let start = datetime::make_date(year, month, 1)?;
 
         let end = datetime::advance_date(&start, duration)?;
 
         Ok(Some((start, end)))
     }
 
-    fn create_report_table(titles: prettytable::Row) -> ReportTable {
-        use prettytable::format;
+    fn week_interval(week: i32, year: i32) -> Result<Interval> {
+        let start = match week {
+            w if w > 0 => {
+                //
+                // Explicit numbers denote ISO weeks, that start on Monday
+                //
+
+                let date = chrono::NaiveDate::from_isoywd_opt(datetime::absolute_year(year), w as u32, chrono::Weekday::Mon)
+                    .ok_or(Error::from_message(errors::INVALID_INTERVAL))?;
+
+                datetime::make_date(date.year(), date.month(), date.day())?
+            },
+            w if year == 0 => {
+                let current_week = datetime::start_of_week(&Clock::now())?;
+                datetime::advance_date(&current_week, datetime::Duration::Week(w))?
+            },
+            _ => return Err(Error::from_message(errors::INVALID_INTERVAL))
+        };
+
+        let end = datetime::advance_date(&start, datetime::Duration::Week(1))?;
+
+        Ok((start, end))
+    }
+
+    fn quarter_interval(quarter: i32, year: i32) -> Result<Interval> {
+        let start = match quarter {
+            q if q > 0 => datetime::make_date(datetime::absolute_year(year), (q as u32 - 1) * 3 + 1, 1)?,
+            q if year == 0 => {
+                let current_quarter = datetime::start_of_quarter(&Clock::now())?;
+                datetime::advance_date(&current_quarter, datetime::Duration::Quarter(q))?
+            },
+            _ => return Err(Error::from_message(errors::INVALID_INTERVAL))
+        };
+
+        let end = datetime::advance_date(&start, datetime::Duration::Quarter(1))?;
+
+        Ok((start, end))
+    }
+}
 
-        let format = format::FormatBuilder::new()
-            .column_separator('│')
-            .borders('│')
-            .separator(
-                format::LinePosition::Top, 
-                format::LineSeparator::new('─', '┬', '┌', '┐')
-            )
-            .separator(
-                format::LinePosition::Title, 
-                format::LineSeparator::new('─', '┼', '├', '┤')
-            )
-            .separator(
-                format::LinePosition::Bottom, 
-                format::LineSeparator::new('─', '┴', '└', '┘')
-            )
-            .padding(1, 1)
-            .build();
 
-        let mut table = ReportTable::new();
-        table.set_format(format);
-        table.set_titles(titles);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(year: i32, month: i32, week: Option<i32>, quarter: Option<i32>) -> Parameters {
+        Parameters {
+            epoch: false,
+            year: year,
+            month: month,
+            week: week,
+            quarter: quarter,
+            target: ReportTarget::CashFlow,
+            no_pager: true,
+            chart: false,
+            descending: false,
+            with_transfers: false
+        }
+    }
+
+    fn interval(parameters: &Parameters) -> (Timestamp, Timestamp) {
+        Report::time_interval(parameters)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn year_covers_calendar_year() {
+        let (start, end) = interval(&parameters(2023, 0, None, None));
+
+        assert_eq!(start, datetime::make_date(2023, 1, 1).unwrap());
+        assert_eq!(end, datetime::make_date(2024, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn month_of_year_covers_month() {
+        let (start, end) = interval(&parameters(2023, 12, None, None));
+
+        assert_eq!(start, datetime::make_date(2023, 12, 1).unwrap());
+        assert_eq!(end, datetime::make_date(2024, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn quarter_and_week_of_year() {
+        let (start, end) = interval(&parameters(2024, 0, None, Some(4)));
+
+        assert_eq!(start, datetime::make_date(2024, 10, 1).unwrap());
+        assert_eq!(end, datetime::make_date(2025, 1, 1).unwrap());
+
+        let (start, end) = interval(&parameters(2020, 0, Some(53), None));
+
+        assert_eq!(start, datetime::make_date(2020, 12, 28).unwrap());
+        assert_eq!(end, datetime::make_date(2021, 1, 4).unwrap());
+    }
 
-        table
+    #[test]
+    fn negative_month_with_year_is_rejected() {
+        assert!(Report::time_interval(&parameters(2023, -1, None, None)).is_err());
     }
 }
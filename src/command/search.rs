@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use libbdgt::datetime::Timestamp;
+use libbdgt::storage::{Account, Category, CategoryType, Id, Transaction};
+
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::error::{Result, Error};
+use crate::console::WritePaged;
+use crate::datetime;
+use crate::binding;
+use crate::console;
+use crate::errors;
+use crate::misc;
+
+
+/// Output format of search results.
+#[derive(Clone, Copy)]
+enum Format {
+    /// Pretty table displayed with pager.
+    Table,
+
+    /// Comma separated values.
+    Csv,
+
+    /// JSON array of objects.
+    Json,
+}
+
+
+/// Description matcher.
+enum Pattern {
+    /// Case-insensitive substring.
+    Substring(String),
+
+    /// Regular expression.
+    Regex(regex::Regex),
+}
+
+
+/// Structure with command parameters.
+pub(crate) struct Parameters {
+    /// Description pattern (if any).
+    pattern: Option<Pattern>,
+
+    /// Minimal absolute amount of money.
+    min_amount: Option<isize>,
+
+    /// Maximal absolute amount of money.
+    max_amount: Option<isize>,
+
+    /// Beginning of the earliest day of transactions.
+    from: Option<Timestamp>,
+
+    /// Beginning of the day after the latest day of transactions.
+    to: Option<Timestamp>,
+
+    /// Names of accounts to search in.
+    accounts: Vec<String>,
+
+    /// Names of categories to search in.
+    categories: Vec<String>,
+
+    /// Type of transactions' categories.
+    category_type: Option<CategoryType>,
+
+    /// Output format.
+    format: Format,
+
+    /// Print results directly without pager.
+    no_pager: bool,
+}
+
+
+/// Transaction search command. Finds transactions matching all specified filters.
+pub(crate) struct Search;
+
+
+impl Command for Search {
+    const VERB: &'static str = "search";
+
+    const ABOUT: &'static str = "Search for transactions";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Searches for transactions, that match all specified filters.",
+        "Amount range is applied to absolute values of amounts.",
+        "Accounts and categories are matched by their names (case-insensitive)."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .arg(clap::arg!([PATTERN] "substring to search for in descriptions (case-insensitive)"))
+            .arg(clap::arg!(-r --regex "treat PATTERN as a regular expression").requires("PATTERN"))
+            .arg(
+                clap::arg!(--min <AMOUNT> "minimal absolute amount of money")
+                    .value_parser(clap::value_parser!(isize))
+            )
+            .arg(
+                clap::arg!(--max <AMOUNT> "maximal absolute amount of money")
+                    .value_parser(clap::value_parser!(isize))
+            )
+            .arg(clap::arg!(--from <DATE> "earliest date of transactions"))
+            .arg(clap::arg!(--to <DATE> "latest date of transactions (inclusive)"))
+            .arg(
+                clap::arg!(-a --account <NAME> "search in the account with specified name")
+                    .action(clap::ArgAction::Append)
+            )
+            .arg(
+                clap::arg!(-c --category <NAME> "search for transactions of the category with specified name")
+                    .action(clap::ArgAction::Append)
+            )
+            .arg(
+                clap::arg!(-i --income "search for income transactions only")
+                    .conflicts_with("outcome")
+            )
+            .arg(
+                clap::arg!(-o --outcome "search for outcome transactions only")
+                    .conflicts_with("income")
+            )
+            .arg(
+                clap::arg!(-f --format [FORMAT] "output format")
+                    .value_parser(["table", "csv", "json"])
+                    .default_value("table")
+            )
+            .arg(clap::arg!(--"no-pager" "print results directly without pager"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let parameters = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+
+        //
+        // Select accounts and categories to search in
+        //
+
+        let accounts = Self::filter_by_name(budget.accounts()?, &parameters.accounts, 
+            |account| &account.name, errors::ACCOUNT_NOT_FOUND)?;
+        let categories = Self::filter_by_name(budget.categories()?, &parameters.categories, 
+            |category| &category.name, errors::CATEGORY_NOT_FOUND)?;
+
+        let categories: HashMap<_, _> = categories
+            .into_iter()
+            .filter(|category| parameters.category_type.is_none_or(|t| t == category.category_type))
+            .map(|category| (category.id.unwrap(), category))
+            .collect();
+
+        //
+        // Query transactions of each account and filter them
+        //
+
+        let mut results = Vec::new();
+        for account in &accounts {
+            let transactions = match (parameters.from, parameters.to) {
+                (Some(from), Some(to)) => budget.transactions_of_between(account.id.unwrap(), from, to)?,
+                _ => budget.transactions_of(account.id.unwrap())?
+            };
+
+            results.extend(transactions
+                .into_iter()
+                .filter(|transaction| Self::matches(&parameters, &categories, transaction))
+                .map(|transaction| (account, transaction)));
+        }
+
+        results.sort_by_key(|(_, transaction)| transaction.timestamp);
+
+        //
+        // And finally print results in requested format
+        //
+
+        let mut output = console::Output::new(parameters.no_pager);
+
+        match parameters.format {
+            Format::Table => Self::format_table(&results, &categories).write_paged(&mut output)?,
+            Format::Csv => Self::format_csv(&results, &categories).write_paged(&mut output)?,
+            Format::Json => Self::format_json(&results, &categories).write_paged(&mut output)?,
+        }
+
+        output.show()?;
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for Search {
+    type ParsedArgs = Parameters;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        let pattern = match Self::get_one_opt::<String>(matches, "PATTERN") {
+            Some(pattern) if Self::get_one(matches, "regex")? => {
+                let regex = regex::RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| Error::from_message_with_extra(errors::PARSE_ERROR, e.to_string()))?;
+
+                Some(Pattern::Regex(regex))
+            },
+            Some(pattern) => Some(Pattern::Substring(pattern.to_lowercase())),
+            None => None
+        };
+
+        //
+        // Bounds are whole days in configured time zone, so the latest
+        // day is included by moving the bound to the next day's start
+        //
+
+        let from = Self::get_one_opt::<String>(matches, "from")
+            .map(|from| Self::parse_day(&from, 0))
+            .transpose()?;
+
+        let to = Self::get_one_opt::<String>(matches, "to")
+            .map(|to| Self::parse_day(&to, 1))
+            .transpose()?;
+
+        let category_type = match (Self::get_one(matches, "income")?, Self::get_one(matches, "outcome")?) {
+            (true, false) => Some(CategoryType::Income),
+            (false, true) => Some(CategoryType::Outcome),
+            _ => None
+        };
+
+        let format = match Self::get_one::<String>(matches, "format")?.as_str() {
+            "csv" => Format::Csv,
+            "json" => Format::Json,
+            _ => Format::Table
+        };
+
+        Ok(Parameters {
+            pattern: pattern,
+            min_amount: Self::get_one_opt(matches, "min"),
+            max_amount: Self::get_one_opt(matches, "max"),
+            from: from,
+            to: to,
+            accounts: Self::get_many(matches, "account"),
+            categories: Self::get_many(matches, "category"),
+            category_type: category_type,
+            format: format,
+            no_pager: Self::get_one(matches, "no-pager")?
+        })
+    }
+}
+
+
+impl Search {
+    fn get_many(matches: &clap::ArgMatches, name: &str) -> Vec<String> {
+        matches.get_many::<String>(name)
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn parse_day(input: &str, shift: i32) -> Result<Timestamp> {
+        let timestamp = datetime::parse_date(input, datetime::DateOrder::configured())?;
+        datetime::advance_date(&timestamp, datetime::Duration::Day(shift))
+    }
+
+    fn filter_by_name<T, F>(items: Vec<T>, names: &[String], name_of: F, not_found: &str) -> Result<Vec<T>>
+    where
+        F: Fn(&T) -> &String
+    {
+        if names.is_empty() {
+            return Ok(items);
+        }
+
+        let matches = |item: &T, name: &String| name.to_lowercase() == name_of(item).to_lowercase();
+
+        //
+        // Unknown name is most likely a typo, hence it is reported
+        // instead of silently finding nothing
+        //
+
+        if let Some(name) = names.iter().find(|name| !items.iter().any(|item| matches(item, name))) {
+            return Err(Error::from_message_with_extra(not_found, name));
+        }
+
+        Ok(items
+            .into_iter()
+            .filter(|item| names.iter().any(|name| matches(item, name)))
+            .collect())
+    }
+
+    fn matches(parameters: &Parameters, categories: &HashMap<Id, Category>, transaction: &Transaction) -> bool {
+        //
+        // Category filter covers both category names and category type
+        //
+
+        if !categories.contains_key(&transaction.category_id) {
+            return false;
+        }
+
+        let description_matches = match &parameters.pattern {
+            Some(Pattern::Substring(pattern)) => transaction.description.to_lowercase().contains(pattern),
+            Some(Pattern::Regex(regex)) => regex.is_match(&transaction.description),
+            None => true
+        };
+
+        let amount = transaction.amount.abs();
+
+        description_matches
+            && parameters.min_amount.is_none_or(|min| amount >= min)
+            && parameters.max_amount.is_none_or(|max| amount <= max)
+            && parameters.from.is_none_or(|from| transaction.timestamp >= from)
+            && parameters.to.is_none_or(|to| transaction.timestamp < to)
+    }
+}
+
+
+impl Search {
+    fn format_table(results: &[(&Account, Transaction)], categories: &HashMap<Id, Category>) -> prettytable::Table {
+        let mut table = common::create_report_table(
+            prettytable::row!["Description", "Amount", "Date and time", "Account", "Category"]);
+
+        for (account, transaction) in results {
+            table.add_row(prettytable::Row::new(vec![
+                prettytable::cell!(transaction.description),
+                prettytable::cell!(r -> console::colorize_amount(transaction.amount)),
+                prettytable::cell!(console::format_timestamp(&transaction.timestamp)),
+                prettytable::cell!(account.name),
+                prettytable::cell!(categories.get(&transaction.category_id).unwrap().name)
+            ]));
+        }
+
+        table
+    }
+
+    fn format_csv(results: &[(&Account, Transaction)], categories: &HashMap<Id, Category>) -> String {
+        let escape = |value: &str| format!("\"{}\"", value.replace('"', "\"\""));
+
+        let mut csv = "description,amount,timestamp,account,category\n".to_string();
+        for (account, transaction) in results {
+            csv.push_str(&format!("{},{},{},{},{}\n",
+                escape(&transaction.description),
+                transaction.amount,
+                transaction.timestamp.to_rfc3339(),
+                escape(&account.name),
+                escape(&categories.get(&transaction.category_id).unwrap().name)
+            ));
+        }
+
+        csv
+    }
+
+    fn format_json(results: &[(&Account, Transaction)], categories: &HashMap<Id, Category>) -> String {
+        let objects: Vec<_> = results
+            .iter()
+            .map(|(account, transaction)| {
+                format!("  {{\"description\": {}, \"amount\": {}, \"timestamp\": {}, \"account\": {}, \"category\": {}}}",
+                    Self::json_string(&transaction.description),
+                    transaction.amount,
+                    Self::json_string(&transaction.timestamp.to_rfc3339()),
+                    Self::json_string(&account.name),
+                    Self::json_string(&categories.get(&transaction.category_id).unwrap().name)
+                )
+            })
+            .collect();
+
+        misc::either!(objects.is_empty()
+            => "[]\n".to_string()
+             ; format!("[\n{}\n]\n", objects.join(",\n"))
+        )
+    }
+
+    fn json_string(value: &str) -> String {
+        let mut result = String::with_capacity(value.len() + 2);
+        result.push('"');
+
+        for c in value.chars() {
+            match c {
+                '"' => result.push_str("\\\""),
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\t' => result.push_str("\\t"),
+                c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+                c => result.push(c)
+            }
+        }
+
+        result.push('"');
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use libbdgt::storage::MetaInfo;
+
+    use super::*;
+
+    fn parameters(from: &str, to: &str) -> Parameters {
+        Parameters {
+            pattern: None,
+            min_amount: None,
+            max_amount: None,
+            from: Some(Search::parse_day(from, 0).unwrap()),
+            to: Some(Search::parse_day(to, 1).unwrap()),
+            accounts: Vec::new(),
+            categories: Vec::new(),
+            category_type: None,
+            format: Format::Table,
+            no_pager: true
+        }
+    }
+
+    fn transaction_at(timestamp: &str) -> Transaction {
+        Transaction {
+            id: None,
+            timestamp: datetime::parse_date(timestamp, datetime::DateOrder::YearMonthDay).unwrap(),
+            description: "Pharmacy".to_owned(),
+            account_id: Id::default(),
+            category_id: Id::default(),
+            amount: -10,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+
+    fn categories() -> HashMap<Id, Category> {
+        HashMap::from([(Id::default(), Category {
+            id: Some(Id::default()),
+            name: "Health".to_owned(),
+            category_type: CategoryType::Outcome,
+            meta_info: MetaInfo::new(None, None, None)
+        })])
+    }
+
+    #[test]
+    fn date_bounds_cover_whole_days() {
+        let parameters = parameters("2024-05-01 15:00", "2024-05-31 08:00");
+
+        assert_eq!(parameters.from, Some(datetime::make_date(2024, 5, 1).unwrap()));
+        assert_eq!(parameters.to, Some(datetime::make_date(2024, 6, 1).unwrap()));
+
+        let matches = |timestamp| Search::matches(&parameters, &categories(), &transaction_at(timestamp));
+
+        assert!(!matches("2024-04-30 23:59"));
+        assert!(matches("2024-05-01 00:00"));
+        assert!(matches("2024-05-01 00:10"));
+        assert!(matches("2024-05-31 23:30"));
+        assert!(!matches("2024-06-01 00:00"));
+    }
+}
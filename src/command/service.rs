@@ -1,7 +1,18 @@
+use std::collections::HashSet;
+
+use libbdgt::datetime::Clock;
+use libbdgt::storage::{Account, Category, CategoryType, Id, MetaInfo, Plan, Transaction};
+
 use super::command::{Command, CommandInternal};
-use crate::error::Result;
+use super::common;
+use crate::journal::{self, Journal};
+use crate::trash::Trash;
+use crate::error::{Result, Error};
+use crate::transfers;
 use crate::console;
 use crate::binding;
+use crate::errors;
+use crate::misc;
 
 
 /// Structure with command parameters.
@@ -11,6 +22,25 @@ pub(crate) struct Parameters {
 
     /// Cleanup removed items.
     cleanup: bool,
+
+    /// Check integrity of the budget.
+    check: bool,
+
+    /// Fix found problems.
+    repair: bool,
+}
+
+
+/// Integrity problem of the budget.
+enum Problem {
+    /// Stored balance differs from the one computed from transactions.
+    BalanceMismatch(Account, isize),
+
+    /// Transaction refers to a missing account or category.
+    OrphanedTransaction(Transaction),
+
+    /// Plan refers to a missing category.
+    OrphanedPlan(Plan),
 }
 
 
@@ -23,10 +53,21 @@ impl Command for Service {
 
     const ABOUT: &'static str = "Enter service mode (for advanced users)";
 
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Enters service mode (for advanced users).",
+        "",
+        "Repair moves orphaned items to trash and adds their copies, that refer to",
+        "accounts and categories chosen by user. Balance of an account is recomputed",
+        "by re-creating the account with all its transactions, so they get new",
+        "identifiers. Reassignments are recorded and can be reverted with 'undo'."
+    );
+
     fn add_args(command: clap::Command) -> clap::Command {
         command
             .arg(clap::arg!(-f --force "skip all confirmations"))
             .arg(clap::arg!(--cleanup "clear all removed items"))
+            .arg(clap::arg!(--check "check balances of accounts and references between items"))
+            .arg(clap::arg!(--repair "check the budget, reassign orphaned items and recompute balances of accounts"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
@@ -46,6 +87,10 @@ impl Command for Service {
 
         let budget = binding::open_budget()?;
 
+        if parameters.check || parameters.repair {
+            Self::check(&budget, parameters.repair, parameters.force)?;
+        }
+
         if parameters.cleanup {
             Self::cleanup(&budget, parameters.force)?;
         }
@@ -61,7 +106,9 @@ impl CommandInternal for Service {
     fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
         Ok(Parameters{
             force: Self::get_one(matches, "force")?,
-            cleanup: Self::get_one(matches, "cleanup")?
+            cleanup: Self::get_one(matches, "cleanup")?,
+            check: Self::get_one(matches, "check")?,
+            repair: Self::get_one(matches, "repair")?
         })
     }
 }
@@ -72,13 +119,255 @@ impl Service {
         if !Self::confirm_with_prompt("You will be unable to sync removed items. Proceed?", force)? {
             return Ok(());
         }
-        
+
         budget.clean_removed()?;
         println!("All removed items are completely deleted.");
 
         Ok(())
     }
 
+    fn check(budget: &binding::Budget, repair: bool, force: bool) -> Result<()> {
+        let problems = Self::find_problems(budget)?;
+
+        if problems.is_empty() {
+            println!("No problems found.");
+            return Ok(());
+        }
+
+        Self::print_problems(&problems)?;
+
+        if !repair {
+            return Ok(());
+        }
+
+        //
+        // Orphaned items are moved to trash and their copies are added
+        // with references to items chosen by user. Both steps are
+        // recorded, so repair can be reverted with 'undo'. Balances
+        // are recomputed last, so reassigned transactions are counted
+        //
+
+        let accounts = budget.accounts()?;
+        let mut trash = Trash::load(budget)?;
+        let mut replaced = Vec::new();
+
+        let is_mismatch = |problem: &&Problem| matches!(problem, Problem::BalanceMismatch(..));
+        let ordered = problems
+            .iter()
+            .filter(|problem| !is_mismatch(problem))
+            .chain(problems.iter().filter(is_mismatch));
+
+        for problem in ordered {
+            let result = match problem {
+                Problem::BalanceMismatch(account, _) => Self::recompute_balance(budget, &mut trash, account, force),
+                Problem::OrphanedTransaction(transaction) => Self::reassign_transaction(budget, &mut trash, transaction, &accounts, force),
+                Problem::OrphanedPlan(plan) => Self::reassign_plan(budget, &mut trash, plan, force)
+            };
+
+            match result {
+                Ok(ids) => replaced.extend(ids),
+                Err(e) => eprintln!("Cannot fix problem: {}", e)
+            }
+        }
+
+        trash.save(budget)?;
+
+        //
+        // Earlier additions refer to the copies now
+        //
+
+        let mut journal = Journal::load(budget)?;
+        journal.replace_ids(&replaced);
+        journal.save(budget)?;
+
+        transfers::replace_ids(budget, &replaced)
+    }
+
+    fn recompute_balance(budget: &binding::Budget, trash: &mut Trash, account: &Account, force: bool) -> Result<Vec<(Id, Id)>> {
+        //
+        // Storage does not allow to change balance directly, and a
+        // compensating transaction changes stored and computed balances
+        // equally. Hence the account is re-created from its snapshot:
+        // restored account starts from its initial balance, and budget
+        // updates it with each restored transaction
+        //
+
+        let prompt = format!("Re-create account '{}' with its transactions to recompute balance?", account.name);
+        if !Self::confirm_with_prompt(&prompt, force)? {
+            return Ok(Vec::new());
+        }
+
+        let id = account.id.unwrap();
+        trash.remove_account(budget, account, true)?;
+
+        let indices = trash.removed_with(id);
+        let replaced = trash.restore(budget, &indices)?;
+
+        match replaced.iter().any(|(old_id, _)| *old_id == id) {
+            true => Ok(replaced),
+            false => Err(Error::from_message(errors::REMOVED_ITEM_NOT_RESTORED))
+        }
+    }
+
+    fn reassign_transaction(budget: &binding::Budget, trash: &mut Trash, transaction: &Transaction, 
+        accounts: &[Account], force: bool) -> Result<Vec<(Id, Id)>> 
+    {
+        println!("Orphaned transaction '{}' ({}, {}).", transaction.description, 
+            console::colorize_amount(transaction.amount), console::format_timestamp(&transaction.timestamp));
+
+        let account_id = match accounts.iter().any(|account| account.id == Some(transaction.account_id)) {
+            true => transaction.account_id,
+            false => Self::select_account(accounts)?
+        };
+
+        let category_type = misc::either!(transaction.amount < 0 => CategoryType::Outcome; CategoryType::Income);
+        let categories = budget.categories_of(category_type)?;
+
+        let category_id = match budget.categories()?.iter().any(|category| category.id == Some(transaction.category_id)) {
+            true => transaction.category_id,
+            false => Self::select_category(&categories)?
+        };
+
+        if !Self::confirm_with_prompt("Reassign transaction?", force)? {
+            return Ok(Vec::new());
+        }
+
+        trash.remove_transaction(budget, transaction)?;
+
+        let id = journal::add_transaction(budget, &Transaction {
+            id: None,
+            timestamp: transaction.timestamp,
+            description: transaction.description.clone(),
+            account_id: account_id,
+            category_id: category_id,
+            amount: transaction.amount,
+            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
+        })?;
+
+        Ok(vec![(transaction.id.unwrap(), id)])
+    }
+
+    fn reassign_plan(budget: &binding::Budget, trash: &mut Trash, plan: &Plan, force: bool) -> Result<Vec<(Id, Id)>> {
+        println!("Orphaned plan '{}'.", plan.name);
+
+        let category_id = Self::select_category(&budget.categories_of(CategoryType::Outcome)?)?;
+
+        if !Self::confirm_with_prompt("Reassign plan?", force)? {
+            return Ok(Vec::new());
+        }
+
+        trash.remove_plan(budget, plan)?;
+
+        let id = journal::add_plan(budget, &Plan {
+            id: None,
+            category_id: category_id,
+            name: plan.name.clone(),
+            amount_limit: plan.amount_limit,
+            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
+        })?;
+
+        Ok(vec![(plan.id.unwrap(), id)])
+    }
+
+    fn select_account(accounts: &[Account]) -> Result<Id> {
+        if accounts.is_empty() {
+            return Err(Error::from_message(errors::NO_ACCOUNTS));
+        }
+
+        let printable_accounts: Vec<_> = accounts
+            .iter()
+            .map(|account| &account.name)
+            .collect();
+
+        let idx = console::fuzzy_select_from_with_prompt(&printable_accounts, 
+            "Which account should it belong to?")?;
+
+        Ok(accounts[idx].id.unwrap())
+    }
+
+    fn select_category(categories: &[Category]) -> Result<Id> {
+        if categories.is_empty() {
+            return Err(Error::from_message(errors::NO_CATEGORIES));
+        }
+
+        let printable_categories: Vec<_> = categories
+            .iter()
+            .map(|category| &category.name)
+            .collect();
+
+        let idx = console::fuzzy_select_from_with_prompt(&printable_categories, 
+            "Which category should it belong to?")?;
+
+        Ok(categories[idx].id.unwrap())
+    }
+
+    fn find_problems(budget: &binding::Budget) -> Result<Vec<Problem>> {
+        let accounts = budget.accounts()?;
+
+        let account_ids: HashSet<_> = accounts
+            .iter()
+            .filter_map(|account| account.id)
+            .collect();
+
+        let category_ids: HashSet<_> = budget.categories()?
+            .iter()
+            .filter_map(|category| category.id)
+            .collect();
+
+        let mut problems = Vec::new();
+
+        //
+        // Balance must be equal to initial balance plus all transactions
+        //
+
+        for account in accounts {
+            let expected = account.initial_balance + budget.transactions_of(account.id.unwrap())?
+                .iter()
+                .map(|transaction| transaction.amount)
+                .sum::<isize>();
+
+            if expected != account.balance {
+                problems.push(Problem::BalanceMismatch(account, expected));
+            }
+        }
+
+        problems.extend(budget.transactions()?
+            .into_iter()
+            .filter(|transaction| {
+                !account_ids.contains(&transaction.account_id) || !category_ids.contains(&transaction.category_id)
+            })
+            .map(Problem::OrphanedTransaction));
+
+        problems.extend(budget.plans()?
+            .into_iter()
+            .filter(|plan| !category_ids.contains(&plan.category_id))
+            .map(Problem::OrphanedPlan));
+
+        Ok(problems)
+    }
+
+    fn print_problems(problems: &[Problem]) -> Result<()> {
+        let mut table = common::create_report_table(
+            prettytable::row!["Problem", "Item", "Details"]);
+
+        for problem in problems {
+            let (kind, item, details) = match problem {
+                Problem::BalanceMismatch(account, expected) => ("Balance mismatch", account.name.clone(),
+                    format!("stored {}, computed {}", console::colorize_amount(account.balance),
+                        console::colorize_amount(*expected))),
+                Problem::OrphanedTransaction(transaction) => ("Orphaned transaction", transaction.description.clone(),
+                    format!("{}, {}", console::colorize_amount(transaction.amount),
+                        console::format_timestamp(&transaction.timestamp))),
+                Problem::OrphanedPlan(plan) => ("Orphaned plan", plan.name.clone(),
+                    "category is removed".to_owned())
+            };
+
+            table.add_row(prettytable::row![kind, item, details]);
+        }
+
+        console::print_paged(&table)
+    }
+
     fn confirm_with_prompt(prompt: &str, force: bool) -> Result<bool> {
         if force {
             return Ok(true);
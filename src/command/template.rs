@@ -0,0 +1,199 @@
+use libbdgt::storage::{Account, Category};
+
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::templates::{Template, Templates};
+use crate::error::{Result, Error};
+use crate::console;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Selection menu entry, that leaves a template field blank.
+const ASK_EVERY_TIME: &str = "(ask every time)";
+
+/// Placeholder for blank template fields in listing.
+const BLANK: &str = "—";
+
+
+/// Requested template action.
+pub(crate) enum Action {
+    /// Create a new template.
+    Add,
+
+    /// Print all templates.
+    List,
+
+    /// Remove a template by name or selected ones.
+    Remove(Option<String>),
+}
+
+
+/// Transaction templates management command.
+pub(crate) struct TemplateCommand;
+
+
+impl Command for TemplateCommand {
+    const VERB: &'static str = "template";
+
+    const ABOUT: &'static str = "Manage transaction templates";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Manages named templates of frequent transactions.",
+        "Template stores account, category, description and default amount.",
+        "Any of them can be left blank to be requested on use.",
+        "Templates are used with 'add --template <NAME>'.",
+        "Templates are stored on this device only and encrypted with the instance's key."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .subcommand_required(true)
+            .subcommand(clap::Command::new("add").about("Create a new template in interactive mode"))
+            .subcommand(clap::Command::new("list").about("Print all templates"))
+            .subcommand(
+                clap::Command::new("remove")
+                    .about("Remove a template (or selected ones if no name specified)")
+                    .arg(clap::arg!([NAME] "name of a template to remove"))
+            )
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let action = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+
+        let mut templates = Templates::load(&budget)?;
+
+        match action {
+            Action::Add => {
+                templates.add(Self::input_template(&budget)?)?;
+                templates.save(&budget)?;
+            },
+            Action::List => Self::print_templates(&budget, &templates)?,
+            Action::Remove(Some(name)) => {
+                templates.remove(&name)?;
+                templates.save(&budget)?;
+            },
+            Action::Remove(None) => {
+                if templates.templates().is_empty() {
+                    return Ok(());
+                }
+
+                let names: Vec<_> = templates
+                    .templates()
+                    .iter()
+                    .map(|template| template.name.clone())
+                    .collect();
+
+                let selection = console::select_multiple_from_with_prompt(
+                    &names, "Select templates to remove")?;
+
+                for idx in selection {
+                    templates.remove(&names[idx])?;
+                }
+
+                templates.save(&budget)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for TemplateCommand {
+    type ParsedArgs = Action;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        match matches.subcommand() {
+            Some(("add", _)) => Ok(Action::Add),
+            Some(("list", _)) => Ok(Action::List),
+            Some(("remove", sub_matches)) => Ok(Action::Remove(Self::get_one_opt(sub_matches, "NAME"))),
+            _ => Err(Error::from_message(errors::PARSE_ERROR))
+        }
+    }
+}
+
+
+impl TemplateCommand {
+    fn input_template(budget: &binding::Budget) -> Result<Template> {
+        let name = console::input_string_with_prompt("Template name")?;
+        let name = name.trim().to_owned();
+
+        //
+        // Check name early to not ask for the rest in vain
+        //
+
+        if name.is_empty() || name.contains(['[', ']']) {
+            return Err(Error::from_message_with_extra(errors::INVALID_TEMPLATE_NAME, name));
+        }
+
+        let accounts = budget.accounts()?;
+        let categories = budget.categories()?;
+
+        let account = Self::select_optional(&accounts, |account: &Account| account.name.clone(),
+            "Which account does transaction belong to?")?
+            .map(|account| account.id.unwrap());
+
+        let category = Self::select_optional(&categories, 
+            |category: &Category| format!("{} ({})", category.name, common::category_type_to_string(category.category_type)),
+            "Which category does transaction belong to?")?
+            .map(|category| category.id.unwrap());
+
+        let description = console::input_optional_string_with_prompt("Description (empty to ask every time)")?;
+
+        let amount = console::input_number_with_prompt("Default amount (0 to ask every time)")?;
+        let amount = misc::either!(amount == 0 => None; Some(amount.abs()));
+
+        Ok(Template {
+            name: name,
+            description: description,
+            account: account,
+            category: category,
+            amount: amount
+        })
+    }
+
+    fn select_optional<'a, T, F>(items: &'a [T], printable: F, prompt: &str) -> Result<Option<&'a T>>
+    where
+        F: Fn(&T) -> String
+    {
+        let mut printable_items = vec![ASK_EVERY_TIME.to_owned()];
+        printable_items.extend(items.iter().map(printable));
+
+        let selection = console::fuzzy_select_from_with_prompt(&printable_items, prompt)?;
+
+        Ok(selection
+            .checked_sub(1)
+            .map(|idx| &items[idx]))
+    }
+
+    fn print_templates(budget: &binding::Budget, templates: &Templates) -> Result<()> {
+        let accounts = budget.accounts()?;
+        let categories = budget.categories()?;
+
+        let mut table = common::create_report_table(
+            prettytable::row!["Name", "Account", "Category", "Description", "Amount"]);
+
+        for template in templates.templates() {
+            let account = template.account
+                .and_then(|id| accounts.iter().find(|account| account.id == Some(id)))
+                .map_or(BLANK.to_owned(), |account| account.name.clone());
+
+            let category = template.category
+                .and_then(|id| categories.iter().find(|category| category.id == Some(id)))
+                .map_or(BLANK.to_owned(), |category| category.name.clone());
+
+            table.add_row(prettytable::Row::new(vec![
+                prettytable::cell!(template.name),
+                prettytable::cell!(account),
+                prettytable::cell!(category),
+                prettytable::cell!(template.description.as_deref().unwrap_or(BLANK)),
+                prettytable::cell!(r -> template.amount.map_or(BLANK.to_owned(), |amount| amount.to_string()))
+            ]));
+        }
+
+        console::print_paged(&table)
+    }
+}
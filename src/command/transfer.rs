@@ -1,13 +1,25 @@
+use std::collections::HashSet;
+
 use libbdgt::datetime::{Clock, Timestamp};
-use libbdgt::storage::{Account, Id};
+use libbdgt::storage::{Account, Category, CategoryType, Id, MetaInfo, Transaction};
 
 use super::command::{Command, CommandInternal};
 use crate::error::{Result, Error};
-use crate::console;
+use crate::console::{self, Review};
+use crate::transfers::{self, RecordedTransfer};
+use crate::journal;
 use crate::binding;
 use crate::errors;
 
 
+/// Description of a transaction, that compensates difference between
+/// sent and received amounts.
+const EXCHANGE_DESCRIPTION: &str = "Exchange difference";
+
+/// Description of a fee transaction.
+const FEE_DESCRIPTION: &str = "Transfer fee";
+
+
 /// Structure with command parameters.
 pub(crate) struct Parameters {
     /// Perform multiple transfers in a row.
@@ -15,6 +27,60 @@ pub(crate) struct Parameters {
 
     /// Input all fields.
     full: bool,
+
+    /// Input received amount separately.
+    exchange: bool,
+
+    /// Input transfer fee.
+    fee: bool,
+
+    /// Save transfers without confirmation.
+    yes: bool,
+}
+
+
+/// Editable field of a transfer.
+#[derive(Clone, Copy)]
+enum Field {
+    /// Source account.
+    From,
+
+    /// Destination account.
+    To,
+
+    /// Sent amount.
+    Sent,
+
+    /// Received amount.
+    Received,
+
+    /// Transfer fee.
+    Fee,
+
+    /// Date and time of the transfer.
+    Timestamp,
+}
+
+
+/// Transfer data entered by user.
+struct TransferData {
+    /// Account, that money is transferred from.
+    from: Id,
+
+    /// Account, that money is transferred to.
+    to: Id,
+
+    /// Amount of money, that leaves source account.
+    sent: isize,
+
+    /// Amount of money, that comes to destination account.
+    received: isize,
+
+    /// Fee amount and its category (if any).
+    fee: Option<(isize, Id)>,
+
+    /// Transfer timestamp.
+    timestamp: Timestamp,
 }
 
 
@@ -31,6 +97,9 @@ impl Command for Transfer {
         command
             .arg(clap::arg!(-m --multi "transfer between several accounts in a row"))
             .arg(clap::arg!(-f --full "configure all possible transfer(s) options"))
+            .arg(clap::arg!(-e --exchange "enter received amount separately (e.g. for currency exchange)"))
+            .arg(clap::arg!(--fee "enter a fee charged for transfer"))
+            .arg(clap::arg!(-y --yes "save transfer(s) without confirmation"))
     }
 
     fn invoke(matches: &clap::ArgMatches) -> Result<()> {
@@ -43,14 +112,19 @@ impl Command for Transfer {
             return Err(Error::from_message(errors::NO_ACCOUNTS));
         }
 
+        //
+        // Fees are supposed to be spendings only
+        //
+
+        let categories = Self::fee_categories(&budget, &parameters)?;
+
         while {
-            let (amount, from, to, timestamp) = Self::input_transfer(parameters.full, &accounts)?;
+            let transfer = Self::input_transfer(&parameters, &accounts, &categories)?;
 
-            if from != to {
-                budget.add_transfer(amount, from, to, timestamp)?;
-            }
-            else {
-                println!("FROM and TO accounts are the same, skipped...");
+            match Self::review_transfer(&parameters, transfer, &accounts, &categories)? {
+                Some(transfer) if transfer.from != transfer.to => Self::add_transfer(&budget, &transfer, &accounts)?,
+                Some(_) => println!("FROM and TO accounts are the same, skipped..."),
+                None => println!("Transfer is discarded.")
             }
 
             //
@@ -58,7 +132,7 @@ impl Command for Transfer {
             //
 
             parameters.multi && Self::needs_another_transfer()?
-        } { /* Intentionally empty */ } 
+        } { /* Intentionally empty */ }
 
         Ok(())
     }
@@ -71,21 +145,137 @@ impl CommandInternal for Transfer {
     fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
         let multi = Self::get_one(matches, "multi")?;
         let full = Self::get_one(matches, "full")?;
+        let exchange = Self::get_one(matches, "exchange")?;
+        let fee = Self::get_one(matches, "fee")?;
+        let yes = Self::get_one(matches, "yes")?;
 
         Ok(Parameters {
             multi: multi,
-            full: full
+            full: full,
+            exchange: exchange,
+            fee: fee,
+            yes: yes
         })
     }
 }
 
 
 impl Transfer {
-    fn input_transfer(full: bool, accounts: &Vec<Account>) -> Result<(isize, Id, Id, Timestamp)> {
+    fn input_transfer(parameters: &Parameters, accounts: &Vec<Account>, categories: &Vec<Category>) -> Result<TransferData> {
         //
         // Ask for 'from' and 'to' accounts
         //
 
+        let from = Self::select_account(accounts, "Which account is intended to transfer FROM?")?;
+        let to = Self::select_account(accounts, "Which account is intended to transfer TO?")?;
+
+        //
+        // Ask for amount. It will be normalized later in `libbdgt::core::Budget`.
+        // Received amount may differ, e.g. if accounts have different currencies.
+        //
+
+        let sent = Self::input_sent()?;
+
+        let received = if Self::separate_received(parameters) {
+            Self::input_received()?
+        }
+        else {
+            sent
+        };
+
+        //
+        // Fee is booked as a separate spending in selected category
+        //
+
+        let fee = if Self::with_fee(parameters) {
+            Self::input_fee(categories)?
+        }
+        else {
+            None
+        };
+
+        let timestamp = if parameters.full {
+            Self::input_timestamp()?
+        }
+        else {
+            Clock::now()
+        };
+
+        Ok(TransferData {
+            from: from,
+            to: to,
+            sent: sent,
+            received: received,
+            fee: fee,
+            timestamp: timestamp
+        })
+    }
+
+    fn review_transfer(parameters: &Parameters, mut transfer: TransferData, accounts: &Vec<Account>, 
+        categories: &Vec<Category>) -> Result<Option<TransferData>>
+    {
+        let name_of = |id: Id| accounts
+            .iter()
+            .find(|account| account.id == Some(id))
+            .map_or(String::new(), |account| account.name.clone());
+
+        if parameters.yes {
+            return Ok(Some(transfer));
+        }
+
+        loop {
+            let mut fields = vec![
+                (Field::From, "From", name_of(transfer.from)),
+                (Field::To, "To", name_of(transfer.to)),
+                (Field::Sent, "Amount", console::colorize_amount(transfer.sent).to_string())
+            ];
+
+            if Self::separate_received(parameters) {
+                fields.push((Field::Received, "Received amount", console::colorize_amount(transfer.received).to_string()));
+            }
+
+            if Self::with_fee(parameters) {
+                let fee = match transfer.fee {
+                    Some((amount, category_id)) => {
+                        let category = categories
+                            .iter()
+                            .find(|category| category.id == Some(category_id))
+                            .unwrap();
+
+                        format!("{} ({})", console::colorize_amount(-amount), category.name)
+                    },
+                    None => "none".to_owned()
+                };
+
+                fields.push((Field::Fee, "Fee", fee));
+            }
+
+            fields.push((Field::Timestamp, "Date and time", console::format_timestamp(&transfer.timestamp)));
+
+            let field = match console::review_with_prompt(&fields, "Save transfer?")? {
+                Review::Save => return Ok(Some(transfer)),
+                Review::Discard => return Ok(None),
+                Review::Edit(field) => field
+            };
+
+            match field {
+                Field::From => transfer.from = Self::select_account(accounts, "Which account is intended to transfer FROM?")?,
+                Field::To => transfer.to = Self::select_account(accounts, "Which account is intended to transfer TO?")?,
+                Field::Sent => {
+                    transfer.sent = Self::input_sent()?;
+
+                    if !Self::separate_received(parameters) {
+                        transfer.received = transfer.sent;
+                    }
+                },
+                Field::Received => transfer.received = Self::input_received()?,
+                Field::Fee => transfer.fee = Self::input_fee(categories)?,
+                Field::Timestamp => transfer.timestamp = Self::input_timestamp()?
+            }
+        }
+    }
+
+    fn select_account(accounts: &Vec<Account>, prompt: &str) -> Result<Id> {
         let printable_accounts: Vec<_> = accounts
             .iter()
             .map(|account| {
@@ -93,34 +283,185 @@ impl Transfer {
             })
             .collect();
 
-        let selection = console::select_from_with_prompt(&printable_accounts, 
-            "Which account is intended to transfer FROM?")?;
+        let selection = console::fuzzy_select_from_with_prompt(&printable_accounts, prompt)?;
+
+        Ok(accounts[selection].id.unwrap())
+    }
+
+    fn input_sent() -> Result<isize> {
+        Ok(console::input_number_with_prompt("Amount")?.abs())
+    }
+
+    fn input_received() -> Result<isize> {
+        Ok(console::input_number_with_prompt("Received amount (in currency of TO account)")?.abs())
+    }
+
+    fn input_fee(categories: &Vec<Category>) -> Result<Option<(isize, Id)>> {
+        if categories.is_empty() {
+            println!("There are no spending categories to book a fee in, fee is skipped.");
+            return Ok(None);
+        }
+
+        let amount = console::input_number_with_prompt("Fee (in currency of FROM account, 0 if none)")?
+            .abs();
+
+        if amount == 0 {
+            return Ok(None);
+        }
+
+        let printable_categories: Vec<_> = categories
+            .iter()
+            .map(|category| &category.name)
+            .collect();
+
+        let selection = console::fuzzy_select_from_with_prompt(&printable_categories,
+            "Which category does fee belong to?")?;
 
-        let from = accounts[selection].id.unwrap();
+        Ok(Some((amount, categories[selection].id.unwrap())))
+    }
 
-        let selection = console::select_from_with_prompt(&printable_accounts, 
-            "Which account is intended to transfer TO?")?;
+    fn input_timestamp() -> Result<Timestamp> {
+        console::input_date_with_prompt("Enter date and time of the transfer")
+    }
 
-        let to = accounts[selection].id.unwrap();
+    fn separate_received(parameters: &Parameters) -> bool {
+        parameters.exchange || parameters.full
+    }
+
+    fn with_fee(parameters: &Parameters) -> bool {
+        parameters.fee || parameters.full
+    }
+
+    fn add_transfer(budget: &binding::Budget, transfer: &TransferData, accounts: &Vec<Account>) -> Result<()> {
+        let name_of = |id| accounts
+            .iter()
+            .find(|account| account.id == Some(id))
+            .map_or(String::new(), |account| account.name.clone());
 
         //
-        // Ask for amount. It will be normalized later in `libbdgt::core::Budget`.
+        // All added transactions are reverted at once
         //
 
-        let amount = console::input_number_with_prompt("Amount")?;
+        let summary = format!("Transfer from '{}' to '{}'", name_of(transfer.from), name_of(transfer.to));
+
+        journal::add_transactions(budget, &[transfer.from, transfer.to], summary, 
+            || Self::add_transfer_transactions(budget, transfer))?;
+
+        Ok(())
+    }
+
+    fn add_transfer_transactions(budget: &binding::Budget, transfer: &TransferData) -> Result<()> {
+        let mut added = Vec::new();
+        let result = Self::add_transfer_legs(budget, transfer, &mut added);
 
-        let timestamp = if full {
-            let datetime = console::input_string_with_prompt("Enter date and time of the transfer")?;
-            dateparser::parse(&datetime)?
+        if result.is_err() {
+            //
+            // Transfer is added either completely or not at all
+            //
+
+            let removed = Clock::now();
+            for id in added.iter().rev() {
+                if let Err(e) = budget.remove_transaction(*id, removed) {
+                    eprintln!("Cannot revert partially added transfer: {}", e);
+                }
+            }
         }
-        else {
-            Clock::now()
+
+        result
+    }
+
+    fn add_transfer_legs(budget: &binding::Budget, transfer: &TransferData, added: &mut Vec<Id>) -> Result<()> {
+        //
+        // Storage does not link transactions of a transfer, hence
+        // their identifiers are recorded (see `crate::transfers`)
+        //
+
+        let known_outgoing = Self::transaction_ids(budget, transfer.from)?;
+        let known_incoming = Self::transaction_ids(budget, transfer.to)?;
+
+        budget.add_transfer(transfer.received, transfer.from, transfer.to, transfer.timestamp)?;
+
+        let outgoing = Self::added_transaction(budget, transfer.from, &known_outgoing);
+        let incoming = Self::added_transaction(budget, transfer.to, &known_incoming);
+
+        added.extend(outgoing.iter().chain(incoming.iter()).filter_map(|transaction| transaction.id));
+
+        let (outgoing, incoming) = (outgoing?, incoming?);
+
+        let mut recorded = RecordedTransfer {
+            outgoing: outgoing.id.unwrap(),
+            incoming: incoming.id.unwrap(),
+            adjustment: None,
+            fee: None
         };
 
-        Ok((amount, from, to, timestamp))
+        if transfer.sent != transfer.received {
+            //
+            // Source account is charged with received amount, hence
+            // the difference is compensated in the same category as
+            // the outgoing transfer transaction
+            //
+
+            let id = Self::add_transaction(budget, &Self::make_transaction(transfer, 
+                EXCHANGE_DESCRIPTION, transfer.received - transfer.sent, outgoing.category_id))?;
+
+            added.push(id);
+            recorded.adjustment = Some(id);
+        }
+
+        if let Some((amount, category_id)) = transfer.fee {
+            let id = Self::add_transaction(budget, &Self::make_transaction(transfer, 
+                FEE_DESCRIPTION, -amount, category_id))?;
+
+            added.push(id);
+            recorded.fee = Some(id);
+        }
+
+        transfers::record(budget, recorded)
+    }
+
+    fn add_transaction(budget: &binding::Budget, transaction: &Transaction) -> Result<Id> {
+        let known = Self::transaction_ids(budget, transaction.account_id)?;
+        budget.add_transaction(transaction)?;
+
+        Ok(Self::added_transaction(budget, transaction.account_id, &known)?.id.unwrap())
+    }
+
+    fn transaction_ids(budget: &binding::Budget, account: Id) -> Result<HashSet<Id>> {
+        Ok(journal::ids(budget.transactions_of(account)?
+            .iter()
+            .map(|transaction| transaction.id)))
+    }
+
+    fn added_transaction(budget: &binding::Budget, account: Id, known: &HashSet<Id>) -> Result<Transaction> {
+        budget.transactions_of(account)?
+            .into_iter()
+            .find(|transaction| transaction.id.is_some_and(|id| !known.contains(&id)))
+            .ok_or(Error::from_message(errors::TRANSFER_NOT_FOUND))
+    }
+
+    fn make_transaction(transfer: &TransferData, description: &str, amount: isize, category_id: Id) -> Transaction {
+        Transaction {
+            id: None,
+            timestamp: transfer.timestamp,
+            description: description.to_owned(),
+            account_id: transfer.from,
+            category_id: category_id,
+            amount: amount,
+            meta_info: MetaInfo::new(Some(Clock::now()), None, None)
+        }
+    }
+
+    fn fee_categories(budget: &binding::Budget, parameters: &Parameters) -> Result<Vec<Category>> {
+        if !parameters.fee && !parameters.full {
+            return Ok(Vec::new());
+        }
+
+        Ok(budget.categories_of(CategoryType::Outcome)?)
     }
 
     fn needs_another_transfer() -> Result<bool> {
         console::confirm_with_prompt("Do you want to add another transfer?", true)
     }
 }
+
@@ -0,0 +1,143 @@
+use super::command::{Command, CommandInternal};
+use super::common;
+use crate::trash::{Item, Trash};
+use crate::journal::Journal;
+use crate::transfers;
+use crate::error::{Result, Error};
+use crate::console;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Requested trash action.
+pub(crate) enum Action {
+    /// Print removed items.
+    List,
+
+    /// Restore selected items.
+    Restore,
+
+    /// Forget all removed items.
+    Clear,
+}
+
+
+/// Removed items management command.
+pub(crate) struct TrashCommand;
+
+
+impl Command for TrashCommand {
+    const VERB: &'static str = "trash";
+
+    const ABOUT: &'static str = "View and restore removed items";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Views and restores accounts, categories, plans and transactions removed on this device.",
+        "Restored items are added as new ones. Transactions can be restored only if their",
+        "account and category exist (or are restored at the same time).",
+        "Storage does not list removed items, hence only items removed with this application",
+        "on this device are available here. Items removed on other instances or before trash",
+        "was introduced cannot be listed or restored."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .subcommand(clap::Command::new("list").about("Print removed items (default)"))
+            .subcommand(clap::Command::new("restore").about("Restore selected items"))
+            .subcommand(clap::Command::new("clear").about("Forget all removed items (they remain removed)"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let action = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+        let mut trash = Trash::load(&budget)?;
+
+        if trash.entries().is_empty() {
+            println!("Trash is empty. Only items removed on this device are tracked, see 'help trash'.");
+            return Ok(());
+        }
+
+        match action {
+            Action::List => Self::print_trash(&trash)?,
+            Action::Restore => {
+                let printable_entries: Vec<_> = trash
+                    .entries()
+                    .iter()
+                    .map(|entry| format!("{} (removed {})", Trash::describe(&entry.item), 
+                        console::format_timestamp(&entry.removed)))
+                    .collect();
+
+                let selection = console::select_multiple_from_with_prompt(
+                    &printable_entries, "Select items to restore")?;
+
+                let replaced = trash.restore(&budget, &selection)?;
+                trash.save(&budget)?;
+
+                //
+                // Restored items have new identifiers, hence 
+                // journal must refer to them from now on. Removals
+                // of restored items cannot be reverted anymore
+                //
+
+                let mut journal = Journal::load(&budget)?;
+                journal.replace_ids(&replaced);
+                journal.forget_removals(&trash);
+                journal.save(&budget)?;
+
+                transfers::replace_ids(&budget, &replaced)?;
+            },
+            Action::Clear => {
+                if console::confirm_with_prompt("Removed items will not be restorable anymore. Proceed?", false)? {
+                    trash.clear();
+                    trash.save(&budget)?;
+
+                    let mut journal = Journal::load(&budget)?;
+                    journal.forget_removals(&trash);
+                    journal.save(&budget)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+impl CommandInternal for TrashCommand {
+    type ParsedArgs = Action;
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        match matches.subcommand() {
+            None | Some(("list", _)) => Ok(Action::List),
+            Some(("restore", _)) => Ok(Action::Restore),
+            Some(("clear", _)) => Ok(Action::Clear),
+            _ => Err(Error::from_message(errors::PARSE_ERROR))
+        }
+    }
+}
+
+
+impl TrashCommand {
+    fn print_trash(trash: &Trash) -> Result<()> {
+        let mut table = common::create_report_table(
+            prettytable::row!["Kind", "Name", "Details", "Removed"]);
+
+        for entry in trash.entries() {
+            let (kind, name, details) = match &entry.item {
+                Item::Account(account) => ("Account", account.name.clone(), 
+                    format!("initial balance: {}", console::colorize_amount(account.initial_balance))),
+                Item::Category(category) => ("Category", category.name.clone(), 
+                    common::category_type_to_string(category.category_type)),
+                Item::Plan(plan) => ("Plan", plan.name.clone(), 
+                    format!("limit: {}", plan.amount_limit)),
+                Item::Transaction(transaction) => ("Transaction", transaction.description.clone(), 
+                    format!("{}, {}", console::colorize_amount(transaction.amount), console::format_timestamp(&transaction.timestamp)))
+            };
+
+            table.add_row(prettytable::row![kind, name, details, console::format_timestamp(&entry.removed)]);
+        }
+
+        console::print_paged(&table)
+    }
+}
@@ -0,0 +1,166 @@
+use libbdgt::datetime::Clock;
+use libbdgt::storage::Id;
+
+use super::command::{Command, CommandInternal};
+use crate::journal::{Journal, Operation};
+use crate::trash::Trash;
+use crate::transfers;
+use crate::error::{Result, Error};
+use crate::console;
+use crate::binding;
+use crate::errors;
+use crate::misc;
+
+
+/// Operations reverting command. Reverts the latest operations performed on this device.
+pub(crate) struct Undo;
+
+
+impl Command for Undo {
+    const VERB: &'static str = "undo";
+
+    const ABOUT: &'static str = "Revert the latest operation(s)";
+
+    const LONG_ABOUT: &'static str = misc::multiline!(
+        "Reverts the latest operations performed on this device in reverse order.",
+        "Added items are removed and removed items are restored as new ones.",
+        "Reverting produces ordinary changes, that are synchronized as usual.",
+        "Use 'history' to see operations, that can be reverted."
+    );
+
+    fn add_args(command: clap::Command) -> clap::Command {
+        command
+            .arg(
+                clap::arg!([COUNT] "number of operations to revert")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .default_value("1")
+            )
+            .arg(clap::arg!(-y --yes "revert without confirmation"))
+    }
+
+    fn invoke(matches: &clap::ArgMatches) -> Result<()> {
+        let (count, yes) = Self::parse_args(matches)?;
+        let budget = binding::open_budget()?;
+        let mut trash = Trash::load(&budget)?;
+
+        //
+        // Removals, which snapshots are gone, cannot be reverted
+        //
+
+        let mut journal = Journal::load(&budget)?;
+        journal.forget_removals(&trash);
+
+        if journal.records().is_empty() {
+            println!("Nothing to undo.");
+            return Ok(());
+        }
+
+        let count = count.min(journal.records().len());
+
+        println!("The following operations will be reverted:");
+        for record in journal.records().iter().rev().take(count) {
+            println!("  {} ({})", record.summary, console::format_timestamp(&record.timestamp));
+        }
+
+        if !yes && !console::confirm_with_prompt("Proceed?", false)? {
+            return Ok(());
+        }
+
+        let mut restored = Vec::new();
+        let mut failure = None;
+
+        for _ in 0..count {
+            let record = journal.last_mut().unwrap();
+
+            match Self::revert(&budget, &mut trash, &mut record.operation) {
+                Ok(replaced) => {
+                    //
+                    // Restored items have new identifiers, hence
+                    // earlier records must refer to them now
+                    //
+
+                    journal.pop();
+                    journal.replace_ids(&replaced);
+                    restored.extend(replaced);
+                },
+                Err(e) => {
+                    failure = Some(Error::from_message_with_extra(errors::OPERATION_NOT_REVERTED, 
+                        format!("'{}': {}", record.summary, e)));
+                    break;
+                }
+            }
+        }
+
+        //
+        // Operations reverted before a failure are saved anyway
+        //
+
+        trash.save(&budget)?;
+        journal.save(&budget)?;
+
+        transfers::replace_ids(&budget, &restored)?;
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(())
+        }
+    }
+}
+
+
+impl CommandInternal for Undo {
+    type ParsedArgs = (usize, bool);
+
+    fn parse_args(matches: &clap::ArgMatches) -> Result<Self::ParsedArgs> {
+        let count: u64 = Self::get_one(matches, "COUNT")?;
+        Ok((count as usize, Self::get_one(matches, "yes")?))
+    }
+}
+
+
+impl Undo {
+    fn revert(budget: &binding::Budget, trash: &mut Trash, operation: &mut Operation) -> Result<Vec<(Id, Id)>> {
+        let removed = Clock::now();
+
+        match operation {
+            Operation::AddTransactions(ids) => {
+                //
+                // Removed transactions are dropped from the record at once,
+                // so it refers to the rest ones only if removal fails
+                //
+
+                while let Some(id) = ids.last() {
+                    budget.remove_transaction(*id, removed)?;
+                    ids.pop();
+                }
+            },
+            Operation::AddAccount(id) => budget.remove_account(*id, false, removed)?,
+            Operation::AddCategory(id) => budget.remove_category(*id, removed)?,
+            Operation::AddPlan(id) => budget.remove_plan(*id, removed)?,
+            Operation::RemoveAccount(id) | Operation::RemoveCategory(id) | Operation::RemovePlan(id) | 
+            Operation::RemoveTransaction(id) => {
+                return Self::restore(budget, trash, *id);
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn restore(budget: &binding::Budget, trash: &mut Trash, id: Id) -> Result<Vec<(Id, Id)>> {
+        //
+        // Removed items can be restored from snapshots only
+        //
+
+        let indices = trash.removed_with(id);
+        if indices.is_empty() {
+            return Err(Error::from_message(errors::REMOVED_ITEM_NOT_FOUND));
+        }
+
+        let replaced = trash.restore(budget, &indices)?;
+
+        match replaced.iter().any(|(old_id, _)| *old_id == id) {
+            true => Ok(replaced),
+            false => Err(Error::from_message(errors::REMOVED_ITEM_NOT_RESTORED))
+        }
+    }
+}
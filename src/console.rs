@@ -1,16 +1,114 @@
 use std::fmt::Write;
+use std::io::IsTerminal;
 
 use colored::Colorize;
 
+use libbdgt::datetime::Timestamp;
+
 use crate::error::Result;
+use crate::expression;
+use crate::preferences;
+use crate::datetime;
+use crate::misc;
+
+
+/// Name of environment variable, that overrides pager usage.
+/// 
+/// Accepted values are `always`, `never` and `auto` (default).
+const PAGER_ENVIRONMENT_VARIABLE: &str = "BDGT_PAGER";
+
+/// Name of environment variable, that disables colored output
+/// if present and not empty. See <https://no-color.org>.
+const NO_COLOR_ENVIRONMENT_VARIABLE: &str = "NO_COLOR";
+
+
+/// User's decision about an entered item.
+pub(crate) enum Review<F> {
+    /// Save the item as is.
+    Save,
+
+    /// Edit the given field.
+    Edit(F),
+
+    /// Discard the item.
+    Discard,
+}
+
+
+/// Destination for a long output.
+/// 
+/// Output is either accumulated in a [`minus::Pager`] and then
+/// displayed interactively, or accumulated in a buffer and then
+/// printed directly into STDOUT.
+pub(crate) enum Output {
+    /// Output is displayed using pager.
+    Paged(minus::Pager),
+
+    /// Output is printed into STDOUT as is.
+    Direct(String),
+}
+
+
+impl Output {
+    /// Creates an output object according to user preferences 
+    /// and STDOUT properties.
+    /// 
+    /// Pager is used by default only if STDOUT is a terminal.
+    /// This behavior can be overridden with `pager` preference or
+    /// `BDGT_PAGER` environment variable (the latter has priority).
+    /// Explicit `no_pager` flag takes precedence over all.
+    /// 
+    /// * `no_pager` - disable pager unconditionally
+    pub(crate) fn new(no_pager: bool) -> Self {
+        if no_pager {
+            return Output::Direct(String::new());
+        }
+
+        let mode = std::env::var(PAGER_ENVIRONMENT_VARIABLE)
+            .unwrap_or_else(|_| preferences::current().pager().to_owned());
 
+        let use_pager = match mode.as_str() {
+            "always" => true,
+            "never" => false,
+            _ => std::io::stdout().is_terminal()
+        };
 
-/// Trait that provides a method of writing something into a [`minus::Pager`].
+        if use_pager {
+            Output::Paged(minus::Pager::new())
+        }
+        else {
+            Output::Direct(String::new())
+        }
+    }
+
+    /// Displays all accumulated data.
+    pub(crate) fn show(self) -> Result<()> {
+        match self {
+            Output::Paged(pager) => minus::page_all(pager)?,
+            Output::Direct(buffer) => print!("{}", buffer)
+        };
+
+        Ok(())
+    }
+}
+
+
+impl std::fmt::Write for Output {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self {
+            Output::Paged(pager) => pager.write_str(s),
+            Output::Direct(buffer) => buffer.write_str(s)
+        }
+    }
+}
+
+
+/// Trait that provides a method of writing something into an [`Output`].
 pub(crate) trait WritePaged {
-    /// Writes data of the object into a [`minus::Pager`].
+    /// Writes data of the object into an [`Output`].
     /// 
-    /// * `pager` - a [`minus::Pager`] instance
-    fn write_paged(&self, pager: &mut minus::Pager) -> Result<()>;
+    /// * `output` - an [`Output`] instance
+    fn write_paged(&self, output: &mut Output) -> Result<()>;
 }
 
 
@@ -18,12 +116,12 @@ pub(crate) trait WritePaged {
 /// If the table is styled using [`colored`] create functions, 
 /// the function preserves all styles.
 impl WritePaged for prettytable::Table {
-    fn write_paged(&self, pager: &mut minus::Pager) -> Result<()> {
+    fn write_paged(&self, output: &mut Output) -> Result<()> {
         let mut buffer = Vec::new();
         self.print(&mut buffer)?;
 
         let string_buffer = String::from_utf8(buffer)?;
-        pager.write_str(&string_buffer)?;
+        output.write_str(&string_buffer)?;
 
         Ok(())
     }
@@ -33,29 +131,109 @@ impl WritePaged for prettytable::Table {
 /// Implementation of [`WritePaged`] for [`String`]. The function
 /// preserves styles set with help of [`colored`] crate.
 impl WritePaged for String {
-    fn write_paged(&self, pager: &mut minus::Pager) -> Result<()> {
-        pager.write_str(&self)?;
+    fn write_paged(&self, output: &mut Output) -> Result<()> {
+        output.write_str(&self)?;
 
         Ok(())
     }
 }
 
 
+/// Prints an object into a new [`Output`] and displays it.
+/// 
+/// * `object` - object to print
+pub(crate) fn print_paged<W: WritePaged>(object: &W) -> Result<()> {
+    let mut output = Output::new(false);
+    object.write_paged(&mut output)?;
+    output.show()
+}
+
+
+/// Returns a plain string, that represents an amount of money.
+/// Currency symbol from preferences is appended if set.
+/// 
+/// * `amount` - amount of money to format
+pub(crate) fn format_amount(amount: isize) -> String {
+    match preferences::current().currency() {
+        Some(currency) => format!("{} {}", amount, currency),
+        None => amount.to_string()
+    }
+}
+
+
 /// Returns a colored string, that represents an amount of
-/// money.
+/// money (see [`format_amount`]).
 /// 
-/// Negative, positive and zero values will be painted red, 
-/// green and yellow respectively. 
+/// Amount is painted according to its sign (see [`paint_by_sign`]).
 /// 
 /// * `amount` - amount of mony to colorize
 pub fn colorize_amount(amount: isize) -> colored::ColoredString {
-    let result = amount.to_string()
-        .bold();
+    let text = format_amount(amount);
+
+    if !colors_enabled() {
+        return text.normal();
+    }
+
+    paint_by_sign(text.bold(), amount)
+}
+
+
+/// Paints a string according to sign of a value and color theme.
+/// 
+/// Default theme paints negative, positive and zero values red, 
+/// green and yellow respectively. Colorblind theme uses magenta,
+/// blue and yellow instead. Style is not changed if colors are
+/// disabled.
+/// 
+/// * `text` - string to paint
+/// * `value` - value, which sign defines color
+pub(crate) fn paint_by_sign(text: colored::ColoredString, value: isize) -> colored::ColoredString {
+    if !colors_enabled() {
+        return text;
+    }
+
+    let colorblind = preferences::current().theme() == "colorblind";
+
+    match value {
+        v if v < 0 => misc::either!(colorblind => text.magenta(); text.red()),
+        0 => text.yellow(),
+        _ => misc::either!(colorblind => text.blue(); text.green())
+    }
+}
+
+
+/// Checks if colored output is allowed by user.
+/// 
+/// Colors are disabled with `mono` theme or if `NO_COLOR`
+/// environment variable is set.
+pub(crate) fn colors_enabled() -> bool {
+    let no_color = std::env::var_os(NO_COLOR_ENVIRONMENT_VARIABLE)
+        .is_some_and(|value| !value.is_empty());
+
+    !no_color && preferences::current().theme() != "mono"
+}
+
+
+/// Formats a timestamp for display according to `date-format` preference.
+/// Timestamp is displayed in configured time zone.
+/// 
+/// * `timestamp` - timestamp to format
+pub(crate) fn format_timestamp(timestamp: &Timestamp) -> String {
+    let timestamp = datetime::to_local(timestamp);
+
+    //
+    // Invalid format makes formatting fail instead of producing a string,
+    // so RFC 2822 is used as a fallback
+    //
 
-    match amount {
-        v if v < 0 => result.red(),
-        0 => result.yellow(),
-        _ => result.green()
+    let mut result = String::new();
+
+    match preferences::current().date_format() {
+        "rfc2822" => timestamp.to_rfc2822(),
+        format => match write!(result, "{}", timestamp.format(format)) {
+            Ok(_) => result,
+            Err(_) => timestamp.to_rfc2822()
+        }
     }
 }
 
@@ -76,18 +254,61 @@ where
 }
 
 
-/// Reads an `isize` from STDIN with printing a prompt before.
+/// Reads an optional string from STDIN with printing a prompt before.
 /// 
-/// Defaults to 0.
+/// Returns `None` if input is empty.
 /// 
 /// * `prompt` - string to display before input
-pub(crate) fn input_number_with_prompt<S>(prompt: S) -> Result<isize>
+pub(crate) fn input_optional_string_with_prompt<S>(prompt: S) -> Result<Option<String>>
+where
+    S: Into<String>
+{
+    let input: String = dialoguer::Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text()?;
+
+    let input = input.trim();
+    Ok(misc::either!(input.is_empty() => None; Some(input.to_owned())))
+}
+
+
+/// Tab-completion using a list of candidates.
+struct Candidates<'a> {
+    /// Candidates in order of priority.
+    items: &'a [String],
+}
+
+
+impl dialoguer::Completion for Candidates<'_> {
+    fn get(&self, input: &str) -> Option<String> {
+        let input = input.to_lowercase();
+
+        self.items
+            .iter()
+            .find(|item| item.to_lowercase().starts_with(&input) && item.len() > input.len())
+            .cloned()
+    }
+}
+
+
+/// Reads a string from STDIN with printing a prompt before.
+/// Pressing `Tab` completes input with the first candidate,
+/// that starts with already entered text (case-insensitive).
+/// 
+/// * `prompt` - string to display before input
+/// * `candidates` - completion candidates in order of priority
+pub(crate) fn input_string_with_completion<S>(prompt: S, candidates: &[String]) -> Result<String>
 where
     S: Into<String>
 {
+    let completion = Candidates {
+        items: candidates
+    };
+
     let input = dialoguer::Input::new()
         .with_prompt(prompt)
-        .with_initial_text("0")
+        .completion_with(&completion)
         .allow_empty(false)
         .interact_text()?;
 
@@ -95,6 +316,72 @@ where
 }
 
 
+/// Reads an `isize` from STDIN with printing a prompt before.
+/// 
+/// Input can be an arithmetic expression, e.g. `120/3` or `45+12.5-3`.
+/// In this case the evaluated (and rounded) result is displayed and
+/// confirmed before it is returned. Defaults to 0.
+/// 
+/// * `prompt` - string to display before input
+pub(crate) fn input_number_with_prompt<S>(prompt: S) -> Result<isize>
+where
+    S: Into<String>
+{
+    let prompt = prompt.into();
+
+    loop {
+        let input: String = dialoguer::Input::new()
+            .with_prompt(&prompt)
+            .with_initial_text("0")
+            .allow_empty(false)
+            .validate_with(|input: &String| expression::evaluate(input).map(|_| ()))
+            .interact_text()?;
+
+        let value = expression::evaluate(&input)?;
+
+        if expression::is_plain_number(&input) {
+            return Ok(value);
+        }
+
+        if confirm_with_prompt(format!("{} = {}. Is it correct?", input.trim(), value), true)? {
+            return Ok(value);
+        }
+    }
+}
+
+
+/// Reads a date and time from STDIN with printing a prompt before.
+/// 
+/// Accepts all formats supported by [`datetime::parse_date`]. 
+/// Numeric dates are interpreted according to `date-order` preference
+/// or `BDGT_DATE_ORDER` environment variable. On error the input is requested again.
+/// Resolved date is displayed and confirmed before it is returned.
+/// 
+/// * `prompt` - string to display before input
+pub(crate) fn input_date_with_prompt<S>(prompt: S) -> Result<Timestamp>
+where
+    S: Into<String>
+{
+    let prompt = prompt.into();
+    let order = datetime::DateOrder::configured();
+
+    loop {
+        let input: String = dialoguer::Input::new()
+            .with_prompt(&prompt)
+            .with_initial_text("today")
+            .allow_empty(false)
+            .validate_with(|input: &String| datetime::parse_date(input, order).map(|_| ()))
+            .interact_text()?;
+
+        let timestamp = datetime::parse_date(&input, order)?;
+
+        if confirm_with_prompt(format!("Resolved as {}. Is it correct?", format_timestamp(&timestamp)), true)? {
+            return Ok(timestamp);
+        }
+    }
+}
+
+
 /// Displays selection menu using given items and prompt.
 /// 
 /// * `items` - items to select from
@@ -114,6 +401,40 @@ where
 }
 
 
+/// Displays selection menu with type-to-filter fuzzy search using given items and prompt.
+/// 
+/// * `items` - items to select from
+/// * `prompt` - string to display before input
+pub(crate) fn fuzzy_select_from_with_prompt<T, S>(items: &[T], prompt: S) -> Result<usize>
+where
+    T: ToString,
+    S: Into<String>
+{
+    fuzzy_select_from_with_default(items, prompt, 0)
+}
+
+
+/// Displays selection menu with type-to-filter fuzzy search using given items,
+/// prompt and preselected item.
+/// 
+/// * `items` - items to select from
+/// * `prompt` - string to display before input
+/// * `default` - index of preselected item
+pub(crate) fn fuzzy_select_from_with_default<T, S>(items: &[T], prompt: S, default: usize) -> Result<usize>
+where
+    T: ToString,
+    S: Into<String>
+{
+    let selection = dialoguer::FuzzySelect::new()
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact()?;
+
+    Ok(selection)
+}
+
+
 /// Displays selection menu with multiple selection using given items and prompt.
 /// 
 /// * `items` - items to select from
@@ -132,6 +453,37 @@ where
 }
 
 
+/// Displays a summary of an entered item and asks whether to save it,
+/// edit one of its fields or discard it.
+/// 
+/// * `fields` - identifiers, names and printable values of item's fields
+/// * `prompt` - string to display before selection
+pub(crate) fn review_with_prompt<F, S>(fields: &[(F, &str, String)], prompt: S) -> Result<Review<F>>
+where
+    F: Copy,
+    S: Into<String>
+{
+    println!();
+    for (_, name, value) in fields {
+        println!("{}: {}", name.bold(), value);
+    }
+
+    println!();
+
+    let mut actions = vec!["Save".to_owned()];
+    actions.extend(fields.iter().map(|(_, name, _)| format!("Edit {}", name.to_lowercase())));
+    actions.push("Discard".to_owned());
+
+    let selection = select_from_with_prompt(&actions, prompt)?;
+
+    Ok(match selection {
+        0 => Review::Save,
+        idx if idx == actions.len() - 1 => Review::Discard,
+        idx => Review::Edit(fields[idx - 1].0)
+    })
+}
+
+
 /// Displays confirmation menu with given default selection and prompt.
 /// 
 /// * `prompt` - string to display before input
@@ -1,9 +1,11 @@
 use libbdgt::datetime::{Clock, Timestamp};
 
-use chrono::{TimeZone, Datelike};
+use chrono::{TimeZone, Datelike, Timelike};
 
 use crate::error::{Result, Error};
 use crate::errors;
+use crate::preferences;
+use crate::misc;
 
 
 /// Type for representing years
@@ -16,112 +18,590 @@ type Month = u32;
 type Day = u32;
 
 
+/// Name of environment variable, that defines order of day, month
+/// and year in numeric dates.
+/// 
+/// Accepted values are `dmy`, `mdy` and `ymd`. Overrides
+/// `date-order` preference.
+const DATE_ORDER_ENVIRONMENT_VARIABLE: &str = "BDGT_DATE_ORDER";
+
+/// Full names of weekdays, that are accepted as dates.
+const WEEKDAYS: [(&str, chrono::Weekday); 7] = [
+    ("monday", chrono::Weekday::Mon),
+    ("tuesday", chrono::Weekday::Tue),
+    ("wednesday", chrono::Weekday::Wed),
+    ("thursday", chrono::Weekday::Thu),
+    ("friday", chrono::Weekday::Fri),
+    ("saturday", chrono::Weekday::Sat),
+    ("sunday", chrono::Weekday::Sun),
+];
+
+
+/// Time zone used to interpret entered dates, to build intervals
+/// and to display timestamps.
+/// 
+/// Timestamps are always stored in UTC, so they remain unambiguous
+/// across synchronized devices in different time zones. Time zone
+/// only affects their interpretation on this device.
+#[derive(Clone, Copy)]
+pub(crate) enum Zone {
+    /// Coordinated Universal Time.
+    Utc,
+
+    /// System time zone (with daylight saving time if applicable).
+    Local,
+
+    /// Fixed offset from UTC.
+    Fixed(chrono::FixedOffset),
+}
+
+
+impl Zone {
+    /// Returns time zone configured with `timezone` preference.
+    /// 
+    /// Defaults to [`Zone::Local`].
+    pub(crate) fn configured() -> Self {
+        Zone::parse(preferences::current().timezone())
+            .unwrap_or(Zone::Local)
+    }
+
+    /// Parses a time zone: `local`, `utc` or an offset like `+03:00`.
+    /// 
+    /// * `value` - string to parse
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "local" => Some(Zone::Local),
+            "utc" => Some(Zone::Utc),
+            offset => offset
+                .parse::<chrono::FixedOffset>()
+                .ok()
+                .map(Zone::Fixed)
+        }
+    }
+
+    /// Converts a date and time in this time zone into a timestamp.
+    /// 
+    /// Ambiguous local time (e.g. when clocks are turned back)
+    /// resolves to the earliest moment. Non-existent local time
+    /// (e.g. when clocks are turned forward) is shifted by an hour.
+    /// 
+    /// * `datetime` - date and time in this time zone
+    pub(crate) fn resolve_local(self, datetime: &chrono::NaiveDateTime) -> Result<Timestamp> {
+        let resolve = |datetime: &chrono::NaiveDateTime| match self {
+            Zone::Utc => Some(chrono::Utc.from_utc_datetime(datetime)),
+            Zone::Local => chrono::Local.from_local_datetime(datetime)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&chrono::Utc)),
+            Zone::Fixed(offset) => offset.from_local_datetime(datetime)
+                .earliest()
+                .map(|datetime| datetime.with_timezone(&chrono::Utc))
+        };
+
+        resolve(datetime)
+            .or_else(|| resolve(&(*datetime + chrono::Duration::hours(1))))
+            .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, datetime.to_string()))
+    }
+
+    /// Converts a timestamp into date and time in this time zone.
+    /// 
+    /// * `timestamp` - timestamp to convert
+    pub(crate) fn localize(self, timestamp: &Timestamp) -> chrono::DateTime<chrono::FixedOffset> {
+        match self {
+            Zone::Utc => timestamp.fixed_offset(),
+            Zone::Local => timestamp.with_timezone(&chrono::Local).fixed_offset(),
+            Zone::Fixed(offset) => timestamp.with_timezone(&offset)
+        }
+    }
+}
+
+
+/// Converts a timestamp into date and time in configured time zone.
+/// 
+/// * `timestamp` - timestamp to convert
+pub(crate) fn to_local(timestamp: &Timestamp) -> chrono::DateTime<chrono::FixedOffset> {
+    Zone::configured().localize(timestamp)
+}
+
+
+/// Order of day, month and year in numeric dates like `01.02.2024`.
+#[derive(Clone, Copy)]
+pub(crate) enum DateOrder {
+    /// Day first, e.g. `31.01.2024`.
+    DayMonthYear,
+
+    /// Month first, e.g. `01/31/2024`.
+    MonthDayYear,
+
+    /// Year first, e.g. `2024-01-31`.
+    YearMonthDay,
+}
+
+
+impl DateOrder {
+    /// Reads date order from `BDGT_DATE_ORDER` environment variable
+    /// or from `date-order` preference.
+    /// 
+    /// Defaults to [`DateOrder::DayMonthYear`].
+    pub(crate) fn configured() -> Self {
+        let order = std::env::var(DATE_ORDER_ENVIRONMENT_VARIABLE)
+            .unwrap_or_else(|_| preferences::current().date_order().to_owned());
+
+        match order.as_str() {
+            "mdy" => DateOrder::MonthDayYear,
+            "ymd" => DateOrder::YearMonthDay,
+            _ => DateOrder::DayMonthYear
+        }
+    }
+}
+
+
 /// Naive duration representation
 pub(crate) enum Duration {
     /// Signed shift in years
     Year(i32),
 
+    /// Signed shift in quarters
+    Quarter(i32),
+
     /// Signed shift in months
     Month(i32),
-}
-
 
-/// Get absolute month from relative to current date.
-/// 
-/// If month is in range \[1, 12\], then the function returns 
-/// the value unchanged. Value 0 is translated into current
-/// month, value -n is translated into the n-th previous month.
-/// Positive value n greater than 12 translates into the
-/// (n - 12)-th next month.
-/// 
-/// * `relative_month` - month relative to the current one
-pub(crate) fn absolute_month(relative_month: i32) -> Month {
-    if 1 <= relative_month && relative_month <= 12 {
-        return relative_month as Month;
-    }
+    /// Signed shift in weeks
+    Week(i32),
 
-    let current_month = Clock::now().month() as i32;
-    match (current_month + relative_month) % 12 {
-        0 => 12 as Month,
-        m => m as Month
-    }
+    /// Signed shift in days
+    Day(i32),
 }
 
 
 /// Get absolute year from relative to current date.
 /// 
+/// Positive values are returned unchanged, value 0 is translated 
+/// into current year, value -n is translated into the n-th previous
+/// year.
+/// 
 /// * `relative_month` - year relative to the current one
 pub(crate) fn absolute_year(relative_year: i32) -> Year {
     if relative_year > 0 {
         return relative_year as Year;
     }
 
-    let current_year = Clock::now().year();
+    let current_year = to_local(&Clock::now()).year();
     current_year + relative_year
 }
 
 
+/// Get absolute year and month from relative ones.
+/// 
+/// Month in range \[1, 12\] denotes a month of year selected with
+/// [`absolute_year`]. Otherwise (0 or negative) month is shifted 
+/// relative to the current month of the selected year, moving 
+/// across year bounds if necessary, e.g. -13 in March 2024 is 
+/// February 2023.
+/// 
+/// * `relative_year` - year relative to the current one
+/// * `relative_month` - month relative to the current one
+pub(crate) fn absolute_year_month(relative_year: i32, relative_month: i32) -> (Year, Month) {
+    let year = absolute_year(relative_year);
+
+    if (1..=12).contains(&relative_month) {
+        return (year, relative_month as Month);
+    }
+
+    let current_month = to_local(&Clock::now()).month();
+    shift_month(year, current_month, relative_month)
+}
+
+
 /// Creates a datetime object from  a calendar date (year, month and day).
+/// The result is a midnight in configured time zone.
 pub(crate) fn make_date(year: Year, month: Month, day: Day) -> Result<Timestamp> {
     let date = chrono::NaiveDate::from_ymd_opt(year, month, day)
         .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, 
             format!("year {year}, month {month}, day {day}")))?;
 
-    let time = chrono::NaiveTime::from_hms_opt(0, 0, 0)
-            .expect("Midnight is a valid time");
+    from_naive_date(&date)
+}
+
 
-    Ok(chrono::Utc.from_utc_datetime(&chrono::NaiveDateTime::new(date, time)))
+/// Returns number of days in a month of a year.
+/// 
+/// * `year` - year, that month belongs to
+/// * `month` - month to count days in
+pub(crate) fn days_in_month(year: Year, month: Month) -> Result<Day> {
+    let error = || Error::from_message_with_extra(errors::INVALID_DATE, 
+        format!("year {year}, month {month}"));
+
+    let start = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(error)?;
+
+    let (next_year, next_month) = shift_month(year, month, 1);
+    let end = chrono::NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .ok_or_else(error)?;
+
+    Ok((end - start).num_days() as Day)
 }
 
 
-/// Shifts a datetime by the specified duration.
+/// Shifts a datetime by the specified duration. Calendar date is taken
+/// in configured time zone, the result is a midnight in that zone.
+/// 
+/// Shifts by months, quarters and years keep day of month, but clamp
+/// it to the last day of the resulting month, e.g. January 31 shifted
+/// by one month is February 28 (or 29 in a leap year).
 /// 
 /// * `origin` - date to shift
 /// * `shift` - duration to shift by
 pub(crate) fn advance_date(origin: &Timestamp, shift: Duration) -> Result<Timestamp> {
-    match shift {
-        Duration::Year(diff) => {
-            advance_date_year(origin, diff)
-        },
-        Duration::Month(diff) => {
-            advance_date_month(origin, diff)
+    let origin = to_local(origin).date_naive();
+
+    let date = match shift {
+        Duration::Year(diff) => advance_date_months(&origin, diff.checked_mul(12))?,
+        Duration::Quarter(diff) => advance_date_months(&origin, diff.checked_mul(3))?,
+        Duration::Month(diff) => advance_date_months(&origin, Some(diff))?,
+        Duration::Week(diff) => advance_date_days(&origin, diff as i64 * 7)?,
+        Duration::Day(diff) => advance_date_days(&origin, diff as i64)?
+    };
+
+    from_naive_date(&date)
+}
+
+
+/// Returns the first day of a week, that contains a timestamp.
+/// Week starts according to `week-start` preference.
+/// 
+/// * `timestamp` - timestamp within the week
+pub(crate) fn start_of_week(timestamp: &Timestamp) -> Result<Timestamp> {
+    let date = to_local(timestamp).date_naive();
+    let week_start = preferences::current().week_start();
+
+    let days_back = (7 + date.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+
+    from_naive_date(&advance_date_days(&date, -(days_back as i64))?)
+}
+
+
+/// Returns the first day of a quarter, that contains a timestamp.
+/// 
+/// * `timestamp` - timestamp within the quarter
+pub(crate) fn start_of_quarter(timestamp: &Timestamp) -> Result<Timestamp> {
+    let date = to_local(timestamp);
+    let first_month = (date.month() - 1) / 3 * 3 + 1;
+
+    make_date(date.year(), first_month, 1)
+}
+
+
+/// Returns ISO 8601 week-numbering year and week number of a timestamp.
+/// 
+/// ISO weeks always start on Monday, and the first week of a year
+/// is the one with the year's first Thursday.
+/// 
+/// * `timestamp` - timestamp to get week number of
+pub(crate) fn iso_week(timestamp: &Timestamp) -> (Year, u32) {
+    let week = to_local(timestamp).iso_week();
+    (week.year(), week.week())
+}
+
+
+fn from_naive_date(date: &chrono::NaiveDate) -> Result<Timestamp> {
+    let time = chrono::NaiveTime::from_hms_opt(0, 0, 0)
+            .expect("Midnight is a valid time");
+
+    Zone::configured().resolve_local(&chrono::NaiveDateTime::new(*date, time))
+}
+
+
+fn shift_month(year: Year, month: Month, shift: i32) -> (Year, Month) {
+    //
+    // Months are counted from the beginning of year 0, so
+    // Euclidean division handles negative shifts properly
+    //
+
+    let total = year as i64 * 12 + (month as i64 - 1) + shift as i64;
+
+    (total.div_euclid(12) as Year, total.rem_euclid(12) as Month + 1)
+}
+
+
+fn advance_date_months(origin: &chrono::NaiveDate, shift: Option<i32>) -> Result<chrono::NaiveDate> {
+    let error = || Error::from_message_with_extra(errors::INVALID_DATE, origin.to_string());
+
+    let (year, month) = shift_month(origin.year(), origin.month(), shift.ok_or_else(error)?);
+    let day = origin.day().min(days_in_month(year, month)?);
+
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+        .ok_or_else(error)
+}
+
+
+fn advance_date_days(origin: &chrono::NaiveDate, shift: i64) -> Result<chrono::NaiveDate> {
+    origin.checked_add_signed(chrono::Duration::days(shift))
+        .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, origin.to_string()))
+}
+
+
+/// Parses a date (and optionally time) entered by user.
+/// 
+/// The following formats are accepted:
+/// - `today`, `yesterday` and `tomorrow`;
+/// - relative offsets like `-3d`, `+1w`, `-2m` or `-1y` (days,
+///   weeks, months and years respectively);
+/// - full weekday names, e.g. `friday`, that denote the latest
+///   such day (today inclusive);
+/// - numeric dates with `.`, `/` or `-` separators, optionally
+///   followed by time `HH:MM[:SS]`. Dates with 4-digit first 
+///   component are always treated as year-first. Year may be
+///   omitted, then the current year is used;
+/// - any other format supported by [`dateparser`] (if input
+///   contains at least one digit).
+/// 
+/// * `input` - string to parse
+/// * `order` - order of day, month and year in numeric dates
+pub(crate) fn parse_date(input: &str, order: DateOrder) -> Result<Timestamp> {
+    let input = input.trim();
+    let lowercase = input.to_lowercase();
+    let now = Clock::now();
+    let local_now = to_local(&now);
+
+    match lowercase.as_str() {
+        "today" => return Ok(now),
+        "yesterday" => return Ok(now - chrono::Duration::days(1)),
+        "tomorrow" => return Ok(now + chrono::Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some((_, weekday)) = WEEKDAYS.iter().find(|(name, _)| *name == lowercase) {
+        let days_back = (7 + local_now.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+        return Ok(now - chrono::Duration::days(days_back as i64));
+    }
+
+    if let Some(timestamp) = parse_relative_date(&lowercase, &now)? {
+        return Ok(timestamp);
+    }
+
+    if let Some(timestamp) = parse_numeric_date(input, order)? {
+        return Ok(timestamp);
+    }
+
+    if input.chars().any(|c| c.is_ascii_digit()) {
+        if let Ok(timestamp) = dateparser::parse(input) {
+            return Ok(timestamp);
         }
     }
+
+    Err(Error::from_message_with_extra(errors::INVALID_DATE, input))
 }
 
 
-fn advance_date_year(origin: &Timestamp, shift: i32) -> Result<Timestamp> {
-    make_date(origin.year() + shift, origin.month(), origin.day())
+fn parse_relative_date(input: &str, now: &Timestamp) -> Result<Option<Timestamp>> {
+    let sign = match input.chars().next() {
+        Some('-') => -1,
+        Some('+') => 1,
+        _ => return Ok(None)
+    };
+
+    let body = &input[1..];
+    let unit = match body.chars().last() {
+        Some(unit) => unit,
+        None => return Ok(None)
+    };
+
+    let value: i32 = match body[..body.len() - unit.len_utf8()].parse() {
+        Ok(value) => value,
+        Err(_) => return Ok(None)
+    };
+
+    let shift = sign * value;
+    let timestamp = match unit {
+        'd' => shift_keeping_time(now, Duration::Day(shift))?,
+        'w' => shift_keeping_time(now, Duration::Week(shift))?,
+        'm' => shift_keeping_time(now, Duration::Month(shift))?,
+        'y' => shift_keeping_time(now, Duration::Year(shift))?,
+        _ => return Ok(None)
+    };
+
+    Ok(Some(timestamp))
 }
 
 
-fn advance_date_month(origin: &Timestamp, shift: i32) -> Result<Timestamp> {
-    let (mut year_shift, month_shift) = (shift / 12, shift % 12);
+fn parse_numeric_date(input: &str, order: DateOrder) -> Result<Option<Timestamp>> {
+    let (date, time) = match input.split_once(|c: char| c.is_whitespace() || c == 'T') {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (input, None)
+    };
 
-    let origin_month = origin.month() as i32;
-    let month = origin_month + month_shift;
+    let mut parts: Vec<_> = date.split(['.', '/', '-']).collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit())) {
+        return Ok(None);
+    }
+
+    let time = match time {
+        Some(time) => {
+            let time = chrono::NaiveTime::parse_from_str(time, "%H:%M:%S")
+                .or_else(|_| chrono::NaiveTime::parse_from_str(time, "%H:%M"));
+
+            match time {
+                Ok(time) => time.num_seconds_from_midnight(),
+                Err(_) => return Ok(None)
+            }
+        },
+        None => 0
+    };
 
     //
-    // Since -12 < month_shift < 12, the following holds: -11 < month < 24.
-    // The following rule applies:
-    // 
-    // +-----------------------+-------------+------------+-------------+
-    // |                 month | -10, ..., 0 | 1, ..., 12 | 13, ..., 23 |
-    // +-----------------------+-------------+------------+-------------+
-    // |             new month |  month + 12 |      month |  month - 12 |
-    // +-----------------------+-------------+------------+-------------+
-    // | additional year shift |          -1 |          0 |           1 |
-    // +-----------------------+-------------+------------+-------------+
+    // 4-digit first component is a year regardless of order
     //
 
-    let (month, additional_year_shift) = match month {
-        m if m < 1 => (m + 12, -1),
-        m if m > 12 => (m - 12, 1),
-        m => (m, 0)
+    let order = misc::either!(parts[0].len() == 4 => DateOrder::YearMonthDay; order);
+
+    //
+    // Omitted year is the current one
+    //
+
+    let current_year = to_local(&Clock::now()).year().to_string();
+
+    if parts.len() == 2 {
+        match order {
+            DateOrder::YearMonthDay => parts.insert(0, &current_year),
+            _ => parts.push(&current_year)
+        }
+    }
+
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|part| part.parse().map_err(|_| Error::from_message_with_extra(errors::INVALID_DATE, input)))
+        .collect::<Result<_>>()?;
+
+    let (year, month, day) = match order {
+        DateOrder::DayMonthYear => (numbers[2], numbers[1], numbers[0]),
+        DateOrder::MonthDayYear => (numbers[2], numbers[0], numbers[1]),
+        DateOrder::YearMonthDay => (numbers[0], numbers[1], numbers[2])
     };
 
-    let month = month as u32;
-    year_shift += additional_year_shift;
+    //
+    // Two-digit years belong to the current century
+    //
+
+    let year = misc::either!(year < 100 => year + 2000; year);
+
+    //
+    // Date and time are entered in configured time zone
+    //
+
+    let date = chrono::NaiveDate::from_ymd_opt(year as Year, month, day)
+        .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, input))?;
+
+    let time = chrono::NaiveTime::from_num_seconds_from_midnight_opt(time, 0)
+        .ok_or(Error::from_message_with_extra(errors::INVALID_DATE, input))?;
+
+    Ok(Some(Zone::configured().resolve_local(&chrono::NaiveDateTime::new(date, time))?))
+}
+
+
+fn shift_keeping_time(origin: &Timestamp, shift: Duration) -> Result<Timestamp> {
+    let time = to_local(origin).num_seconds_from_midnight();
+    let date = advance_date(origin, shift)?;
+
+    Ok(date + chrono::Duration::seconds(time as i64))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn local_date(timestamp: &Timestamp) -> (Year, Month, Day) {
+        let date = to_local(timestamp);
+        (date.year(), date.month(), date.day())
+    }
+
+    fn shift(year: Year, month: Month, day: Day, duration: Duration) -> (Year, Month, Day) {
+        local_date(&advance_date(&make_date(year, month, day).unwrap(), duration).unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn month_shift_round_trips(year in 1900..2200, month in 1u32..=12, diff in -2400..2400) {
+            let (shifted_year, shifted_month) = shift_month(year, month, diff);
 
-    make_date(origin.year() + year_shift, month, origin.day())
+            prop_assert!((1..=12).contains(&shifted_month));
+            prop_assert_eq!(shift_month(shifted_year, shifted_month, -diff), (year, month));
+        }
+
+        #[test]
+        fn month_shift_clamps_day(year in 1900..2200, month in 1u32..=12, day in 1u32..=31, diff in -240..240) {
+            prop_assume!(day <= days_in_month(year, month).unwrap());
+
+            let (expected_year, expected_month) = shift_month(year, month, diff);
+            let expected_day = day.min(days_in_month(expected_year, expected_month).unwrap());
+
+            prop_assert_eq!(shift(year, month, day, Duration::Month(diff)), (expected_year, expected_month, expected_day));
+        }
+
+        #[test]
+        fn quarter_and_year_shifts_are_month_shifts(year in 1900..2200, month in 1u32..=12, day in 1u32..=28, diff in -100..100) {
+            prop_assert_eq!(shift(year, month, day, Duration::Quarter(diff)), shift(year, month, day, Duration::Month(diff * 3)));
+            prop_assert_eq!(shift(year, month, day, Duration::Year(diff)), shift(year, month, day, Duration::Month(diff * 12)));
+        }
+    }
+
+    #[test]
+    fn days_in_month_respects_leap_years() {
+        assert_eq!(days_in_month(2024, 2).unwrap(), 29);
+        assert_eq!(days_in_month(2023, 2).unwrap(), 28);
+        assert_eq!(days_in_month(2000, 2).unwrap(), 29);
+        assert_eq!(days_in_month(1900, 2).unwrap(), 28);
+        assert_eq!(days_in_month(2023, 12).unwrap(), 31);
+        assert!(days_in_month(2023, 13).is_err());
+    }
+
+    #[test]
+    fn month_end_is_clamped() {
+        assert_eq!(shift(2024, 1, 31, Duration::Month(1)), (2024, 2, 29));
+        assert_eq!(shift(2023, 1, 31, Duration::Month(1)), (2023, 2, 28));
+        assert_eq!(shift(2024, 3, 31, Duration::Month(-1)), (2024, 2, 29));
+        assert_eq!(shift(2024, 2, 29, Duration::Year(1)), (2025, 2, 28));
+        assert_eq!(shift(2023, 8, 31, Duration::Quarter(1)), (2023, 11, 30));
+    }
+
+    #[test]
+    fn iso_week_53_is_recognized() {
+        assert_eq!(iso_week(&make_date(2020, 12, 31).unwrap()), (2020, 53));
+        assert_eq!(iso_week(&make_date(2021, 1, 3).unwrap()), (2020, 53));
+        assert_eq!(iso_week(&make_date(2021, 1, 4).unwrap()), (2021, 1));
+        assert_eq!(iso_week(&make_date(2026, 12, 31).unwrap()), (2026, 53));
+        assert_eq!(iso_week(&make_date(2024, 12, 30).unwrap()), (2025, 1));
+    }
+
+    #[test]
+    fn quarters_start_at_boundaries() {
+        for month in 1..=12 {
+            let start = start_of_quarter(&make_date(2024, month, 15).unwrap()).unwrap();
+            assert_eq!(local_date(&start), (2024, (month - 1) / 3 * 3 + 1, 1));
+        }
+
+        assert_eq!(shift(2024, 10, 1, Duration::Quarter(1)), (2025, 1, 1));
+        assert_eq!(shift(2024, 1, 1, Duration::Quarter(-1)), (2023, 10, 1));
+    }
+
+    #[test]
+    fn dst_months_keep_calendar_dates() {
+        //
+        // Daylight saving time usually changes in March, April, 
+        // October and November, so each day of these months must
+        // map to itself regardless of skipped or repeated hours
+        //
+
+        for month in [3, 4, 10, 11] {
+            for day in 1..=days_in_month(2024, month).unwrap() {
+                let date = make_date(2024, month, day).unwrap();
+
+                assert_eq!(local_date(&date), (2024, month, day));
+                assert_eq!(shift(2024, month, day, Duration::Day(1)), local_date(&(date + chrono::Duration::hours(36))));
+            }
+        }
+    }
 }
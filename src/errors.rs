@@ -16,8 +16,65 @@ pub(crate) const NO_ACCOUNTS: &str = "No accounts exist yet";
 /// No categories present, when at least one is required.
 pub(crate) const NO_CATEGORIES: &str = "No categories exist yet";
 
+/// Arithmetic expression cannot be parsed.
+pub(crate) const INVALID_EXPRESSION: &str = "Invalid arithmetic expression";
+
+/// Division by zero in arithmetic expression.
+pub(crate) const DIVISION_BY_ZERO: &str = "Division by zero";
+
 /// Invalid time interval specified.
 pub(crate) const INVALID_INTERVAL: &str = "Invalid time interval specified";
 
 /// Date cannot be built from provided arguments.
 pub(crate) const INVALID_DATE: &str = "Invalid date specified";
+
+/// Account with specified name does not exist.
+pub(crate) const ACCOUNT_NOT_FOUND: &str = "Account not found";
+
+/// Category with specified name does not exist.
+pub(crate) const CATEGORY_NOT_FOUND: &str = "Category not found";
+
+/// Transfer transactions cannot be found after transfer creation.
+pub(crate) const TRANSFER_NOT_FOUND: &str = "Cannot find transactions of the transfer";
+
+/// Local file of the application cannot be parsed.
+pub(crate) const INVALID_LOCAL_FILE: &str = "Invalid content of a local file";
+
+/// Newly created item cannot be found after creation.
+pub(crate) const CREATED_ITEM_NOT_FOUND: &str = "Cannot find just created item";
+
+/// Template with specified name does not exist.
+pub(crate) const TEMPLATE_NOT_FOUND: &str = "Template not found";
+
+/// Template with specified name already exists.
+pub(crate) const TEMPLATE_EXISTS: &str = "Template already exists";
+
+/// Template name contains forbidden characters.
+pub(crate) const INVALID_TEMPLATE_NAME: &str = "Template name cannot be blank or contain '[' and ']'";
+
+/// Item cannot be restored, since an item it refers to does not exist.
+pub(crate) const MISSING_DEPENDENCY: &str = "Referenced account or category does not exist";
+
+/// Setting with specified name does not exist.
+pub(crate) const UNKNOWN_SETTING: &str = "Unknown setting";
+
+/// Value is not allowed for a setting.
+pub(crate) const INVALID_SETTING_VALUE: &str = "Invalid setting value";
+
+/// Some tokens of a quick-entry line cannot be interpreted.
+pub(crate) const UNKNOWN_TOKENS: &str = "Cannot interpret tokens";
+
+/// Chart is requested for a report, that is displayed as a table only.
+pub(crate) const CHART_NOT_AVAILABLE: &str = "Chart is not available for accounts and transfers reports";
+
+/// Removed item cannot be found among saved snapshots.
+pub(crate) const REMOVED_ITEM_NOT_FOUND: &str = "Removed item is not found in trash";
+
+/// Removed item cannot be restored from its snapshot.
+pub(crate) const REMOVED_ITEM_NOT_RESTORED: &str = "Removed item cannot be restored";
+
+/// Recorded operation cannot be reverted.
+pub(crate) const OPERATION_NOT_REVERTED: &str = "Cannot undo operation";
+
+/// Item with specified name does not exist.
+pub(crate) const ITEM_NOT_FOUND: &str = "Item not found";
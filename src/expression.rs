@@ -0,0 +1,297 @@
+use crate::error::{Result, Error};
+use crate::errors;
+
+
+/// Number of fractional units in one integer unit. Intermediate
+/// values are stored as fixed-point numbers with this scale.
+const SCALE: i128 = 1_000_000;
+
+
+/// Maximal nesting level of parentheses and unary signs. Parser
+/// is recursive, hence nesting is limited to keep stack bounded.
+const MAX_DEPTH: usize = 64;
+
+
+/// Fixed-point number used during evaluation.
+type Value = i128;
+
+
+/// Recursive descent parser and evaluator of arithmetic expressions.
+///
+/// Grammar:
+///
+/// ```text
+/// expression := term (('+' | '-') term)*
+/// term       := factor (('*' | '/') factor)*
+/// factor     := ('+' | '-') factor | number | '(' expression ')'
+/// number     := digits (('.' | ',') digits)?
+/// ```
+struct Parser<'a> {
+    /// Source expression.
+    source: &'a str,
+
+    /// Characters of the expression without whitespaces.
+    chars: Vec<char>,
+
+    /// Current position in `chars`.
+    position: usize,
+
+    /// Current nesting level.
+    depth: usize,
+}
+
+
+/// Evaluates an arithmetic expression and returns its value rounded
+/// to the nearest integer.
+///
+/// Supports `+`, `-`, `*`, `/`, unary signs, parentheses and
+/// decimal numbers (with `.` or `,` as a separator). Division by
+/// zero and overflow are reported as errors.
+///
+/// * `expression` - expression to evaluate
+pub(crate) fn evaluate(expression: &str) -> Result<isize> {
+    let mut parser = Parser::new(expression);
+
+    let value = parser.expression()?;
+    if parser.peek().is_some() {
+        return Err(parser.error());
+    }
+
+    //
+    // Round half away from zero
+    //
+
+    let half = SCALE / 2;
+    let rounded = match value {
+        v if v < 0 => v.checked_sub(half),
+        v => v.checked_add(half)
+    };
+
+    rounded
+        .and_then(|v| isize::try_from(v / SCALE).ok())
+        .ok_or_else(overflow)
+}
+
+
+/// Checks if a string is a plain integer number, i.e. it needs no evaluation.
+///
+/// * `expression` - string to check
+pub(crate) fn is_plain_number(expression: &str) -> bool {
+    expression.trim().parse::<isize>().is_ok()
+}
+
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Parser {
+            source: source,
+            chars: source.chars().filter(|c| !c.is_whitespace()).collect(),
+            position: 0,
+            depth: 0
+        }
+    }
+
+    fn expression(&mut self) -> Result<Value> {
+        let mut value = self.term()?;
+
+        while let Some(op) = self.peek() {
+            value = match op {
+                '+' => { self.position += 1; value.checked_add(self.term()?) },
+                '-' => { self.position += 1; value.checked_sub(self.term()?) },
+                _ => break
+            }.ok_or_else(overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Value> {
+        let mut value = self.factor()?;
+
+        while let Some(op) = self.peek() {
+            value = match op {
+                '*' => {
+                    self.position += 1;
+                    let rhs = self.factor()?;
+
+                    value.checked_mul(rhs)
+                        .map(|v| v / SCALE)
+                },
+                '/' => {
+                    self.position += 1;
+                    let rhs = self.factor()?;
+
+                    if rhs == 0 {
+                        return Err(Error::from_message_with_extra(errors::DIVISION_BY_ZERO, self.source));
+                    }
+
+                    value.checked_mul(SCALE)
+                        .map(|v| v / rhs)
+                },
+                _ => break
+            }.ok_or_else(overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<Value> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::from_message_with_extra(errors::INVALID_EXPRESSION, 
+                format!("nesting is deeper than {} levels", MAX_DEPTH)));
+        }
+
+        self.depth += 1;
+        let value = self.nested_factor();
+        self.depth -= 1;
+
+        value
+    }
+
+    fn nested_factor(&mut self) -> Result<Value> {
+        match self.peek() {
+            Some('+') => {
+                self.position += 1;
+                self.factor()
+            },
+            Some('-') => {
+                self.position += 1;
+                self.factor()?
+                    .checked_neg()
+                    .ok_or_else(overflow)
+            },
+            Some('(') => {
+                self.position += 1;
+                let value = self.expression()?;
+
+                match self.peek() {
+                    Some(')') => {
+                        self.position += 1;
+                        Ok(value)
+                    },
+                    _ => Err(self.error())
+                }
+            },
+            Some(c) if c.is_ascii_digit() => self.number(),
+            _ => Err(self.error())
+        }
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let integer = self.digits();
+        let integer: Value = integer.parse()
+            .map_err(|_| overflow())?;
+
+        let mut value = integer.checked_mul(SCALE)
+            .ok_or_else(overflow)?;
+
+        if let Some('.' | ',') = self.peek() {
+            self.position += 1;
+
+            let fraction = self.digits();
+            if fraction.is_empty() {
+                return Err(self.error());
+            }
+
+            //
+            // Digits beyond scale precision are truncated
+            //
+
+            let mut unit = SCALE;
+            for digit in fraction.chars() {
+                unit /= 10;
+                value = value.checked_add(digit.to_digit(10).unwrap() as Value * unit)
+                    .ok_or_else(overflow)?;
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn digits(&mut self) -> String {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+
+            self.position += 1;
+        }
+
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn error(&self) -> Error {
+        let extra = match self.peek() {
+            Some(c) => format!("unexpected '{}' in '{}'", c, self.source),
+            None => format!("unexpected end of '{}'", self.source)
+        };
+
+        Error::from_message_with_extra(errors::INVALID_EXPRESSION, extra)
+    }
+}
+
+
+fn overflow() -> Error {
+    Error::from_message(errors::VALUE_OUT_OF_RANGE)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence_is_respected() {
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), 14);
+        assert_eq!(evaluate("(2 + 3) * 4").unwrap(), 20);
+        assert_eq!(evaluate("20 - 6 / 2 - 1").unwrap(), 16);
+        assert_eq!(evaluate("2 * -3").unwrap(), -6);
+        assert_eq!(evaluate("-(4 - 10)").unwrap(), 6);
+    }
+
+    #[test]
+    fn results_are_rounded() {
+        assert_eq!(evaluate("10 / 3").unwrap(), 3);
+        assert_eq!(evaluate("5 / 2").unwrap(), 3);
+        assert_eq!(evaluate("-5 / 2").unwrap(), -3);
+        assert_eq!(evaluate("1,5 * 3").unwrap(), 5);
+        assert_eq!(evaluate("0.1 + 0.2").unwrap(), 0);
+    }
+
+    #[test]
+    fn division_by_zero_is_reported() {
+        assert!(evaluate("1 / 0").is_err());
+        assert!(evaluate("1 / (2 - 2)").is_err());
+        assert!(evaluate("1 / 0.0000001").is_err());
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        assert!(evaluate("99999999999999999999999999999999999999999").is_err());
+        assert!(evaluate(&format!("{} + 1", isize::MAX)).is_err());
+        assert!(evaluate(&format!("{} * {}", isize::MAX, isize::MAX)).is_err());
+        assert_eq!(evaluate(&isize::MAX.to_string()).unwrap(), isize::MAX);
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expression in ["", "1 +", "(1", "1)", "2 ** 3", "1.", "abc"] {
+            assert!(evaluate(expression).is_err(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert_eq!(evaluate(&nested(MAX_DEPTH - 1)).unwrap(), 1);
+        assert!(evaluate(&nested(MAX_DEPTH)).is_err());
+        assert!(evaluate(&nested(100_000)).is_err());
+        assert!(evaluate(&"-".repeat(100_000)).is_err());
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashSet;
+
+use libbdgt::datetime::{Clock, Timestamp};
+use libbdgt::storage::{Account, Category, Id, Plan, Transaction};
+
+use crate::error::{Result, Error};
+use crate::store::{self, Section};
+use crate::trash::Trash;
+use crate::binding;
+use crate::errors;
+
+
+/// Name of a local file with performed operations.
+const JOURNAL_FILE: &str = "journal";
+
+/// Maximal number of operations remembered.
+const JOURNAL_LIMIT: usize = 100;
+
+
+/// Mutating operation performed through the budget.
+pub(crate) enum Operation {
+    /// Transactions added at once (single transaction or a transfer).
+    AddTransactions(Vec<Id>),
+
+    /// Account added.
+    AddAccount(Id),
+
+    /// Category added.
+    AddCategory(Id),
+
+    /// Plan added.
+    AddPlan(Id),
+
+    /// Account removed (possibly with its transactions).
+    RemoveAccount(Id),
+
+    /// Category removed.
+    RemoveCategory(Id),
+
+    /// Plan removed.
+    RemovePlan(Id),
+
+    /// Transaction removed.
+    RemoveTransaction(Id),
+}
+
+
+/// Journal record.
+pub(crate) struct Record {
+    /// Performed operation.
+    pub operation: Operation,
+
+    /// Human-readable description of the operation.
+    pub summary: String,
+
+    /// Time of the operation.
+    pub timestamp: Timestamp,
+}
+
+
+/// Operations performed on this device.
+///
+/// Operations are recorded with identifiers of affected items,
+/// so they can be reverted later. Reverting produces ordinary
+/// changes (removals and additions), that are synchronized as
+/// usual. Removed items are restored from [`crate::trash::Trash`].
+pub(crate) struct Journal {
+    /// Records in order of operations.
+    records: Vec<Record>,
+}
+
+
+impl Journal {
+    /// Loads journal from storage root.
+    ///
+    /// * `budget` - budget, which key protects the journal
+    pub(crate) fn load(budget: &binding::Budget) -> Result<Self> {
+        Self::from_sections(&store::read_protected_sections(budget, JOURNAL_FILE)?)
+    }
+
+    /// Saves journal into storage root.
+    ///
+    /// * `budget` - budget, which key protects the journal
+    pub(crate) fn save(&self, budget: &binding::Budget) -> Result<()> {
+        store::write_protected_sections(budget, JOURNAL_FILE, &self.to_sections())
+    }
+
+    /// Creates journal from sections of a local file.
+    ///
+    /// * `sections` - sections to read records from
+    pub(crate) fn from_sections(sections: &[Section]) -> Result<Self> {
+        let records = sections
+            .iter()
+            .filter(|section| !section.name.is_empty())
+            .map(Self::from_section)
+            .collect::<Result<_>>()?;
+
+        Ok(Journal {
+            records: records
+        })
+    }
+
+    /// Converts journal into sections of a local file.
+    pub(crate) fn to_sections(&self) -> Vec<Section> {
+        self.records
+            .iter()
+            .map(Self::to_section)
+            .collect()
+    }
+
+    /// Returns all records from the oldest to the latest one.
+    pub(crate) fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Appends a record. The oldest records are forgotten
+    /// if there are too many of them.
+    ///
+    /// * `operation` - performed operation
+    /// * `summary` - description of the operation
+    pub(crate) fn push(&mut self, operation: Operation, summary: String) {
+        self.records.push(Record {
+            operation: operation,
+            summary: summary,
+            timestamp: Clock::now()
+        });
+
+        if self.records.len() > JOURNAL_LIMIT {
+            self.records.drain(..self.records.len() - JOURNAL_LIMIT);
+        }
+    }
+
+    /// Removes the latest record.
+    pub(crate) fn pop(&mut self) -> Option<Record> {
+        self.records.pop()
+    }
+
+    /// Returns the latest record for modification.
+    pub(crate) fn last_mut(&mut self) -> Option<&mut Record> {
+        self.records.last_mut()
+    }
+
+    /// Updates identifiers of added items, that were re-created.
+    ///
+    /// Removals refer to snapshots in trash, that keep original
+    /// identifiers, hence they are not updated.
+    ///
+    /// * `replaced` - pairs of old and new identifiers
+    pub(crate) fn replace_ids(&mut self, replaced: &[(Id, Id)]) {
+        let replace = |id: &mut Id| {
+            if let Some((_, new_id)) = replaced.iter().find(|(old_id, _)| old_id == id) {
+                *id = *new_id;
+            }
+        };
+
+        for record in &mut self.records {
+            match &mut record.operation {
+                Operation::AddTransactions(ids) => ids.iter_mut().for_each(replace),
+                Operation::AddAccount(id) | Operation::AddCategory(id) | Operation::AddPlan(id) => replace(id),
+                Operation::RemoveAccount(_) | Operation::RemoveCategory(_) | Operation::RemovePlan(_) | 
+                Operation::RemoveTransaction(_) => {}
+            }
+        }
+    }
+
+    /// Drops removals, which snapshots are not in trash anymore
+    /// (restored or cleared). Such removals cannot be reverted.
+    ///
+    /// * `trash` - current content of trash
+    pub(crate) fn forget_removals(&mut self, trash: &Trash) {
+        self.records.retain(|record| match record.operation {
+            Operation::RemoveAccount(id) | Operation::RemoveCategory(id) | Operation::RemovePlan(id) | 
+            Operation::RemoveTransaction(id) => {
+                !trash.removed_with(id).is_empty()
+            },
+            _ => true
+        });
+    }
+}
+
+
+/// Appends a record to the journal in storage root.
+///
+/// * `budget` - budget, which key protects the journal
+/// * `operation` - performed operation
+/// * `summary` - description of the operation
+pub(crate) fn record(budget: &binding::Budget, operation: Operation, summary: String) -> Result<()> {
+    let mut journal = Journal::load(budget)?;
+    journal.push(operation, summary);
+    journal.save(budget)
+}
+
+
+/// Adds a transaction and records it. Returns identifier of the
+/// added transaction.
+///
+/// * `budget` - budget to add transaction into
+/// * `transaction` - transaction to add
+pub(crate) fn add_transaction(budget: &binding::Budget, transaction: &Transaction) -> Result<Id> {
+    add_transactions(budget, &[transaction.account_id],
+        format!("Add transaction '{}'", transaction.description),
+        || Ok(budget.add_transaction(transaction)?))?
+        .first()
+        .copied()
+        .ok_or(Error::from_message(errors::CREATED_ITEM_NOT_FOUND))
+}
+
+
+/// Performs additions of transactions and records all of them
+/// as a single operation. Returns identifiers of added transactions.
+///
+/// * `budget` - budget to add transactions into
+/// * `accounts` - accounts, that transactions are added to
+/// * `summary` - description of the operation
+/// * `add` - function, that adds transactions
+pub(crate) fn add_transactions<F>(budget: &binding::Budget, accounts: &[Id], summary: String, add: F) -> Result<Vec<Id>>
+where
+    F: FnOnce() -> Result<()>
+{
+    let transactions_of = |budget: &binding::Budget| -> Result<HashSet<Id>> {
+        let mut ids = HashSet::new();
+        for account in accounts {
+            ids.extend(budget.transactions_of(*account)?
+                .into_iter()
+                .filter_map(|transaction| transaction.id));
+        }
+
+        Ok(ids)
+    };
+
+    let known = transactions_of(budget)?;
+
+    add()?;
+
+    let added: Vec<_> = transactions_of(budget)?
+        .into_iter()
+        .filter(|id| !known.contains(id))
+        .collect();
+
+    record(budget, Operation::AddTransactions(added.clone()), summary)?;
+
+    Ok(added)
+}
+
+
+/// Adds an account, records it and returns its identifier.
+///
+/// * `budget` - budget to add account into
+/// * `account` - account to add
+pub(crate) fn add_account(budget: &binding::Budget, account: &Account) -> Result<Id> {
+    let known = ids(budget.accounts()?.iter().map(|account| account.id));
+
+    budget.add_account(account)?;
+
+    let id = added_id(&known, budget.accounts()?.iter().map(|account| account.id))?;
+    record(budget, Operation::AddAccount(id), format!("Add account '{}'", account.name))?;
+
+    Ok(id)
+}
+
+
+/// Adds a category, records it and returns its identifier.
+///
+/// * `budget` - budget to add category into
+/// * `category` - category to add
+pub(crate) fn add_category(budget: &binding::Budget, category: &Category) -> Result<Id> {
+    let known = ids(budget.categories()?.iter().map(|category| category.id));
+
+    budget.add_category(category)?;
+
+    let id = added_id(&known, budget.categories()?.iter().map(|category| category.id))?;
+    record(budget, Operation::AddCategory(id), format!("Add category '{}'", category.name))?;
+
+    Ok(id)
+}
+
+
+/// Adds a plan, records it and returns its identifier.
+///
+/// * `budget` - budget to add plan into
+/// * `plan` - plan to add
+pub(crate) fn add_plan(budget: &binding::Budget, plan: &Plan) -> Result<Id> {
+    let known = ids(budget.plans()?.iter().map(|plan| plan.id));
+
+    budget.add_plan(plan)?;
+
+    let id = added_id(&known, budget.plans()?.iter().map(|plan| plan.id))?;
+    record(budget, Operation::AddPlan(id), format!("Add plan '{}'", plan.name))?;
+
+    Ok(id)
+}
+
+
+/// Collects identifiers of items.
+///
+/// * `ids` - optional identifiers of items
+pub(crate) fn ids<I>(ids: I) -> HashSet<Id>
+where
+    I: Iterator<Item = Option<Id>>
+{
+    ids.flatten().collect()
+}
+
+
+/// Returns an identifier, that is not known yet.
+///
+/// * `known` - identifiers of items before addition
+/// * `ids` - identifiers of items after addition
+pub(crate) fn added_id<I>(known: &HashSet<Id>, mut ids: I) -> Result<Id>
+where
+    I: Iterator<Item = Option<Id>>
+{
+    ids.find_map(|id| id.filter(|id| !known.contains(id)))
+        .ok_or(Error::from_message(errors::CREATED_ITEM_NOT_FOUND))
+}
+
+
+impl Journal {
+    fn from_section(section: &Section) -> Result<Record> {
+        let error = || Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, JOURNAL_FILE);
+        let get = |key: &str| section.get(key).ok_or_else(error);
+
+        let ids = get("ids")?
+            .split(',')
+            .filter(|id| !id.is_empty())
+            .map(store::decode_id)
+            .collect::<Result<Vec<_>>>()?;
+
+        let single = || ids.first().copied().ok_or_else(error);
+
+        let operation = match section.name.as_str() {
+            "add-transactions" => Operation::AddTransactions(ids.clone()),
+            "add-account" => Operation::AddAccount(single()?),
+            "add-category" => Operation::AddCategory(single()?),
+            "add-plan" => Operation::AddPlan(single()?),
+            "remove-account" => Operation::RemoveAccount(single()?),
+            "remove-category" => Operation::RemoveCategory(single()?),
+            "remove-plan" => Operation::RemovePlan(single()?),
+            "remove-transaction" => Operation::RemoveTransaction(single()?),
+            _ => return Err(error())
+        };
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339(get("timestamp")?)
+            .map(|timestamp| timestamp.with_timezone(&chrono::Utc))
+            .map_err(|_| error())?;
+
+        Ok(Record {
+            operation: operation,
+            summary: get("summary")?.to_owned(),
+            timestamp: timestamp
+        })
+    }
+
+    fn to_section(record: &Record) -> Section {
+        let (name, ids) = match &record.operation {
+            Operation::AddTransactions(ids) => ("add-transactions", ids.clone()),
+            Operation::AddAccount(id) => ("add-account", vec![*id]),
+            Operation::AddCategory(id) => ("add-category", vec![*id]),
+            Operation::AddPlan(id) => ("add-plan", vec![*id]),
+            Operation::RemoveAccount(id) => ("remove-account", vec![*id]),
+            Operation::RemoveCategory(id) => ("remove-category", vec![*id]),
+            Operation::RemovePlan(id) => ("remove-plan", vec![*id]),
+            Operation::RemoveTransaction(id) => ("remove-transaction", vec![*id])
+        };
+
+        let ids: Vec<_> = ids
+            .iter()
+            .map(store::encode_id)
+            .collect();
+
+        let mut section = Section::new(name);
+        section.set("ids", ids.join(","));
+        section.set("summary", record.summary.clone());
+        section.set("timestamp", record.timestamp.to_rfc3339());
+        section
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(value: u8) -> Id {
+        let mut id = Id::default();
+        id[0] = value;
+        id
+    }
+
+    fn removed_category(id: &Id) -> Section {
+        let mut section = Section::new(format!("category:{}", store::encode_id(id)));
+        section.set("name", "Food");
+        section.set("type", "outcome");
+        section.set("removed", "2024-03-01T10:00:00+00:00");
+        section
+    }
+
+    fn journal() -> Journal {
+        let mut journal = Journal::from_sections(&[]).unwrap();
+        journal.push(Operation::AddCategory(id(1)), "Add category 'Food'".to_owned());
+        journal.push(Operation::AddTransactions(vec![id(2), id(3)]), "Add transaction 'Lunch'".to_owned());
+        journal.push(Operation::RemoveCategory(id(1)), "Remove category 'Food'".to_owned());
+        journal
+    }
+
+    fn summaries(journal: &Journal) -> Vec<&str> {
+        journal.records()
+            .iter()
+            .map(|record| record.summary.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let journal = journal();
+        let parsed = Journal::from_sections(&journal.to_sections()).unwrap();
+
+        assert_eq!(summaries(&parsed), summaries(&journal));
+
+        for (parsed, record) in parsed.records().iter().zip(journal.records()) {
+            assert_eq!(parsed.timestamp.timestamp(), record.timestamp.timestamp());
+            assert_eq!(Journal::to_section(parsed).values, Journal::to_section(record).values);
+        }
+
+        assert!(matches!(&parsed.records()[1].operation, Operation::AddTransactions(ids) if *ids == vec![id(2), id(3)]));
+    }
+
+    #[test]
+    fn journal_is_limited() {
+        let mut journal = Journal::from_sections(&[]).unwrap();
+        for idx in 0..JOURNAL_LIMIT + 5 {
+            journal.push(Operation::AddPlan(id(1)), idx.to_string());
+        }
+
+        assert_eq!(journal.records().len(), JOURNAL_LIMIT);
+        assert_eq!(journal.records()[0].summary, "5");
+    }
+
+    #[test]
+    fn removals_in_trash_are_kept() {
+        let trash = Trash::from_sections(&[removed_category(&id(1))]).unwrap();
+
+        let mut journal = journal();
+        journal.forget_removals(&trash);
+
+        assert_eq!(journal.records().len(), 3);
+    }
+
+    #[test]
+    fn removals_are_forgotten_with_trash_entries() {
+        let mut trash = Trash::from_sections(&[removed_category(&id(1))]).unwrap();
+        trash.clear();
+
+        let mut journal = journal();
+        journal.forget_removals(&trash);
+
+        assert_eq!(summaries(&journal), ["Add category 'Food'", "Add transaction 'Lunch'"]);
+    }
+
+    #[test]
+    fn transaction_removals_follow_trash_entries() {
+        let mut section = Section::new(format!("transaction:{}", store::encode_id(&id(2))));
+        section.set("timestamp", "2024-03-01T09:00:00+00:00");
+        section.set("description", "Lunch");
+        section.set("account", store::encode_id(&id(4)));
+        section.set("category", store::encode_id(&id(1)));
+        section.set("amount", "-50");
+        section.set("removed", "2024-03-01T10:00:00+00:00");
+
+        let mut trash = Trash::from_sections(&[section]).unwrap();
+
+        let mut journal = Journal::from_sections(&[]).unwrap();
+        journal.push(Operation::RemoveTransaction(id(2)), "Remove transaction 'Lunch'".to_owned());
+
+        let mut journal = Journal::from_sections(&journal.to_sections()).unwrap();
+        journal.forget_removals(&trash);
+
+        assert!(matches!(journal.records()[0].operation, Operation::RemoveTransaction(old_id) if old_id == id(2)));
+
+        trash.clear();
+        journal.forget_removals(&trash);
+
+        assert!(journal.records().is_empty());
+    }
+
+    #[test]
+    fn only_additions_refer_to_recreated_items() {
+        let trash = Trash::from_sections(&[removed_category(&id(1))]).unwrap();
+
+        let mut journal = journal();
+        journal.replace_ids(&[(id(1), id(9))]);
+
+        assert!(matches!(journal.records()[0].operation, Operation::AddCategory(new_id) if new_id == id(9)));
+        assert!(matches!(journal.records()[2].operation, Operation::RemoveCategory(old_id) if old_id == id(1)));
+
+        //
+        // Removal still refers to a snapshot in trash
+        //
+
+        journal.forget_removals(&trash);
+        assert_eq!(journal.records().len(), 3);
+    }
+}
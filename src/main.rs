@@ -9,9 +9,20 @@ extern crate anyhow;
 extern crate chrono;
 extern crate minus;
 extern crate clap;
+extern crate terminal_size;
+extern crate regex;
 
 mod datetime;
 mod console;
+mod chart;
+mod transfers;
+mod suggestions;
+mod templates;
+mod store;
+mod preferences;
+mod trash;
+mod journal;
+mod expression;
 mod command;
 mod binding;
 mod errors;
@@ -52,7 +63,11 @@ fn run() -> Result<()> {
     handle_commands!(
         command::Initialize,
         command::Report,
+        command::Forecast,
+        command::Search,
         command::AddTransaction,
+        command::QuickAdd,
+        command::TemplateCommand,
         command::Transfer,
         command::AddAccount,
         command::AddCategory,
@@ -60,8 +75,12 @@ fn run() -> Result<()> {
         command::RemoveAccount,
         command::RemoveCategory,
         command::RemovePlan,
+        command::TrashCommand,
+        command::Undo,
+        command::History,
         command::Sync,
         command::Service,
+        command::ConfigCommand,
         command::About,
     )
 }
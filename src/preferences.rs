@@ -0,0 +1,268 @@
+use std::sync::OnceLock;
+
+use crate::error::{Result, Error};
+use crate::store::{self, Section};
+use crate::binding;
+use crate::datetime;
+use crate::errors;
+use crate::misc;
+
+
+/// Name of a local file with preferences.
+const PREFERENCES_FILE: &str = "preferences";
+
+
+/// Description of a single setting.
+pub(crate) struct Setting {
+    /// Name of the setting.
+    pub key: &'static str,
+
+    /// Short description.
+    pub about: &'static str,
+
+    /// Value used if the setting is not set.
+    pub default: &'static str,
+
+    /// Allowed values. Empty if any value is allowed.
+    pub values: &'static [&'static str],
+}
+
+
+/// All known settings.
+pub(crate) const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "report-target",
+        about: "report built when no target is specified",
+        default: "accounts",
+        values: &["accounts", "categories", "plans", "cash-flow", "transfers"]
+    },
+    Setting {
+        key: "date-format",
+        about: "format of displayed dates: 'rfc2822' or a strftime-like format, e.g. '%d.%m.%Y %H:%M'",
+        default: "rfc2822",
+        values: &[]
+    },
+    Setting {
+        key: "date-order",
+        about: "order of day, month and year in entered numeric dates",
+        default: "dmy",
+        values: &["dmy", "mdy", "ymd"]
+    },
+    Setting {
+        key: "pager",
+        about: "pager usage for long output",
+        default: "auto",
+        values: &["auto", "always", "never"]
+    },
+    Setting {
+        key: "currency",
+        about: "currency symbol displayed after amounts",
+        default: "",
+        values: &[]
+    },
+    Setting {
+        key: "timezone",
+        about: "time zone for entered and displayed dates: 'local', 'utc' or an offset like '+03:00'",
+        default: "local",
+        values: &[]
+    },
+    Setting {
+        key: "week-start",
+        about: "first day of a week",
+        default: "monday",
+        values: &["monday", "sunday"]
+    },
+    Setting {
+        key: "default-account",
+        about: "name of an account preselected for new transactions",
+        default: "",
+        values: &[]
+    },
+    Setting {
+        key: "theme",
+        about: "color theme",
+        default: "default",
+        values: &["default", "colorblind", "mono"]
+    },
+];
+
+
+/// Preferences loaded once per process.
+static CURRENT: OnceLock<Preferences> = OnceLock::new();
+
+
+/// User preferences stored in a local file.
+/// 
+/// Preferences are not synchronized, since different devices
+/// may need different settings. Command line flags and environment
+/// variables take precedence over preferences.
+#[derive(Default)]
+pub(crate) struct Preferences {
+    /// Explicitly set values.
+    values: Section,
+}
+
+
+/// Returns current preferences. Defaults are used if storage is not
+/// initialized yet or if preferences cannot be loaded (the latter is
+/// reported).
+pub(crate) fn current() -> &'static Preferences {
+    CURRENT.get_or_init(|| {
+        if binding::storage_root().is_err() {
+            return Preferences::default();
+        }
+
+        Preferences::load().unwrap_or_else(|e| {
+            eprintln!("Cannot load preferences, defaults are used: {}", e);
+            Preferences::default()
+        })
+    })
+}
+
+
+impl Preferences {
+    /// Loads preferences from storage root. Unknown settings and
+    /// invalid values (e.g. edited manually) are reported and skipped.
+    pub(crate) fn load() -> Result<Self> {
+        let values = store::read_sections(PREFERENCES_FILE)?
+            .into_iter()
+            .find(|section| section.name.is_empty())
+            .unwrap_or_default();
+
+        let mut preferences = Preferences::default();
+
+        for (key, value) in values.values {
+            if let Err(e) = preferences.set(&key, &value) {
+                eprintln!("Preference '{}' is ignored: {}", key, e);
+            }
+        }
+
+        Ok(preferences)
+    }
+
+    /// Saves preferences into storage root.
+    pub(crate) fn save(&self) -> Result<()> {
+        store::write_sections(PREFERENCES_FILE, std::slice::from_ref(&self.values))
+    }
+
+    /// Returns value of a setting or its default value.
+    /// 
+    /// * `key` - name of the setting
+    pub(crate) fn get(&self, key: &str) -> Result<&str> {
+        let setting = Self::setting(key)?;
+
+        Ok(self.values
+            .get(setting.key)
+            .unwrap_or(setting.default))
+    }
+
+    /// Checks if a setting is set explicitly.
+    /// 
+    /// * `key` - name of the setting
+    pub(crate) fn is_set(&self, key: &str) -> bool {
+        self.values.get(key).is_some()
+    }
+
+    /// Sets a value of a setting.
+    /// 
+    /// * `key` - name of the setting
+    /// * `value` - new value
+    pub(crate) fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let setting = Self::setting(key)?;
+        let value = value.trim();
+
+        if !setting.values.is_empty() && !setting.values.contains(&value) {
+            return Err(Error::from_message_with_extra(errors::INVALID_SETTING_VALUE, 
+                format!("{} (possible values: {})", value, setting.values.join(", "))));
+        }
+
+        if setting.key == "date-format" && !Self::is_valid_date_format(value) {
+            return Err(Error::from_message_with_extra(errors::INVALID_SETTING_VALUE, value));
+        }
+
+        if setting.key == "timezone" && datetime::Zone::parse(value).is_none() {
+            return Err(Error::from_message_with_extra(errors::INVALID_SETTING_VALUE, value));
+        }
+
+        self.values.set(setting.key, value);
+
+        Ok(())
+    }
+
+    /// Resets a setting to its default value.
+    /// 
+    /// * `key` - name of the setting
+    pub(crate) fn unset(&mut self, key: &str) -> Result<()> {
+        let setting = Self::setting(key)?;
+        self.values.remove(setting.key);
+
+        Ok(())
+    }
+
+    /// Report built when no target is specified.
+    pub(crate) fn report_target(&self) -> &str {
+        self.value("report-target")
+    }
+
+    /// Format of displayed dates.
+    pub(crate) fn date_format(&self) -> &str {
+        self.value("date-format")
+    }
+
+    /// Order of day, month and year in entered numeric dates.
+    pub(crate) fn date_order(&self) -> &str {
+        self.value("date-order")
+    }
+
+    /// Time zone for entered and displayed dates.
+    pub(crate) fn timezone(&self) -> &str {
+        self.value("timezone")
+    }
+
+    /// Pager usage for long output.
+    pub(crate) fn pager(&self) -> &str {
+        self.value("pager")
+    }
+
+    /// Currency symbol displayed after amounts (if any).
+    pub(crate) fn currency(&self) -> Option<&str> {
+        Some(self.value("currency"))
+            .filter(|currency| !currency.is_empty())
+    }
+
+    /// First day of a week.
+    pub(crate) fn week_start(&self) -> chrono::Weekday {
+        misc::either!(self.value("week-start") == "sunday"
+            => chrono::Weekday::Sun
+             ; chrono::Weekday::Mon
+        )
+    }
+
+    /// Name of an account preselected for new transactions (if any).
+    pub(crate) fn default_account(&self) -> Option<&str> {
+        Some(self.value("default-account"))
+            .filter(|account| !account.is_empty())
+    }
+
+    /// Color theme.
+    pub(crate) fn theme(&self) -> &str {
+        self.value("theme")
+    }
+
+    fn value(&self, key: &str) -> &str {
+        self.get(key)
+            .expect("only known settings are queried internally")
+    }
+
+    fn setting(key: &str) -> Result<&'static Setting> {
+        SETTINGS
+            .iter()
+            .find(|setting| setting.key == key)
+            .ok_or(Error::from_message_with_extra(errors::UNKNOWN_SETTING, key))
+    }
+
+    fn is_valid_date_format(format: &str) -> bool {
+        format == "rfc2822" || !chrono::format::StrftimeItems::new(format)
+            .any(|item| matches!(item, chrono::format::Item::Error))
+    }
+}
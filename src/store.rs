@@ -0,0 +1,316 @@
+use std::path::PathBuf;
+
+use libbdgt::storage::Id;
+
+use crate::error::{Result, Error};
+use crate::binding;
+use crate::errors;
+
+
+/// Named group of key-value pairs in a local file.
+/// 
+/// Values, that precede the first section header, belong
+/// to a section with an empty name.
+#[derive(Default)]
+pub(crate) struct Section {
+    /// Name of the section.
+    pub name: String,
+
+    /// Key-value pairs in order of appearance.
+    pub values: Vec<(String, String)>,
+}
+
+
+impl Section {
+    /// Creates an empty section.
+    /// 
+    /// * `name` - name of the section
+    pub(crate) fn new<S: Into<String>>(name: S) -> Self {
+        Section {
+            name: name.into(),
+            values: Vec::new()
+        }
+    }
+
+    /// Returns value by key if present.
+    /// 
+    /// * `key` - key to look for
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a value by key, replacing an existing one.
+    /// 
+    /// * `key` - key to set value for
+    /// * `value` - new value
+    pub(crate) fn set<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>
+    {
+        let key = key.into();
+        let value = value.into();
+
+        match self.values.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((key, value))
+        }
+    }
+
+    /// Removes a value by key if present.
+    /// 
+    /// * `key` - key to remove value for
+    pub(crate) fn remove(&mut self, key: &str) {
+        self.values.retain(|(k, _)| k != key);
+    }
+}
+
+
+/// Returns path of a local (not synchronized) file in application's storage root.
+/// 
+/// * `file_name` - name of the file
+pub(crate) fn local_file(file_name: &str) -> Result<PathBuf> {
+    Ok(binding::storage_root()?.join(file_name))
+}
+
+
+/// Reads sections from a local file. Missing file contains no sections.
+/// 
+/// * `file_name` - name of the file in storage root
+pub(crate) fn read_sections(file_name: &str) -> Result<Vec<Section>> {
+    let path = local_file(file_name)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    parse_sections(&std::fs::read_to_string(&path)?)
+        .map_err(|_| Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, path.display().to_string()))
+}
+
+
+/// Writes sections into a local file replacing its content.
+/// 
+/// * `file_name` - name of the file in storage root
+/// * `sections` - sections to write
+pub(crate) fn write_sections(file_name: &str, sections: &[Section]) -> Result<()> {
+    std::fs::write(local_file(file_name)?, format_sections(sections))?;
+
+    Ok(())
+}
+
+
+/// Reads sections from a local file encrypted with the instance's key.
+/// Missing file contains no sections.
+/// 
+/// * `budget` - budget, which key protects the file
+/// * `file_name` - name of the file in storage root
+pub(crate) fn read_protected_sections(budget: &binding::Budget, file_name: &str) -> Result<Vec<Section>> {
+    let path = local_file(file_name)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let error = || Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, path.display().to_string());
+
+    let content = binding::decrypt_local(budget, &std::fs::read(&path)?)?;
+    let content = String::from_utf8(content)
+        .map_err(|_| error())?;
+
+    parse_sections(&content)
+        .map_err(|_| error())
+}
+
+
+/// Writes sections into a local file encrypted with the instance's key.
+/// 
+/// * `budget` - budget, which key protects the file
+/// * `file_name` - name of the file in storage root
+/// * `sections` - sections to write
+pub(crate) fn write_protected_sections(budget: &binding::Budget, file_name: &str, sections: &[Section]) -> Result<()> {
+    let content = binding::encrypt_local(budget, format_sections(sections).as_bytes())?;
+    std::fs::write(local_file(file_name)?, content)?;
+
+    Ok(())
+}
+
+
+/// Parses sections from a text.
+/// 
+/// Text consists of `[name]` section headers and `key = value`
+/// lines. Empty lines and lines starting with `#` are ignored.
+/// 
+/// * `content` - text to parse
+pub(crate) fn parse_sections(content: &str) -> Result<Vec<Section>> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            sections.push(Section::new(name.trim()));
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')
+            .ok_or(Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, 
+                format!("line {}", number + 1)))?;
+
+        if sections.is_empty() {
+            sections.push(Section::default());
+        }
+
+        sections.last_mut()
+            .unwrap()
+            .set(key.trim(), value.trim());
+    }
+
+    Ok(sections)
+}
+
+
+/// Formats sections into a text, that can be parsed back.
+/// 
+/// Only the first section may have no header. Headers of other
+/// sections are written even if names are empty, so their values
+/// are not merged into a previous section.
+/// 
+/// * `sections` - sections to format
+pub(crate) fn format_sections(sections: &[Section]) -> String {
+    let mut content = String::new();
+
+    for (idx, section) in sections.iter().enumerate() {
+        if idx != 0 || !section.name.is_empty() {
+            content.push_str(&format!("[{}]\n", section.name));
+        }
+
+        for (key, value) in &section.values {
+            content.push_str(&format!("{} = {}\n", key, value));
+        }
+
+        content.push('\n');
+    }
+
+    content
+}
+
+
+/// Converts an identifier into a hexadecimal string.
+/// 
+/// * `id` - identifier to convert
+pub(crate) fn encode_id(id: &Id) -> String {
+    id.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+
+/// Converts a hexadecimal string back into an identifier.
+/// 
+/// * `value` - string to convert
+pub(crate) fn decode_id(value: &str) -> Result<Id> {
+    let error = || Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, value);
+
+    let mut id = Id::default();
+    if value.len() != 2 * id.len() || !value.is_ascii() {
+        return Err(error());
+    }
+
+    for (idx, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&value[2 * idx..2 * idx + 2], 16)
+            .map_err(|_| error())?;
+    }
+
+    Ok(id)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(name: &str, values: &[(&str, &str)]) -> Section {
+        let mut section = Section::new(name);
+        for (key, value) in values {
+            section.set(*key, *value);
+        }
+
+        section
+    }
+
+    fn assert_sections_eq(left: &[Section], right: &[Section]) {
+        assert_eq!(left.len(), right.len());
+
+        for (left, right) in left.iter().zip(right) {
+            assert_eq!(left.name, right.name);
+            assert_eq!(left.values, right.values);
+        }
+    }
+
+    #[test]
+    fn sections_round_trip() {
+        let sections = vec![
+            section("", &[("pager", "never")]),
+            section("first", &[("a", "1"), ("b", "x = y")]),
+            section("second", &[]),
+        ];
+
+        let parsed = parse_sections(&format_sections(&sections)).unwrap();
+        assert_sections_eq(&parsed, &sections);
+    }
+
+    #[test]
+    fn blank_section_names_are_not_merged() {
+        let sections = vec![
+            section("first", &[("a", "1")]),
+            section("", &[("a", "2")]),
+        ];
+
+        let parsed = parse_sections(&format_sections(&sections)).unwrap();
+        assert_sections_eq(&parsed, &sections);
+    }
+
+    #[test]
+    fn comments_and_empty_lines_are_ignored() {
+        let parsed = parse_sections("# comment\n\n  key =  value  \n[ name ]\n# other = 1\nk=v\n").unwrap();
+
+        assert_sections_eq(&parsed, &[
+            section("", &[("key", "value")]),
+            section("name", &[("k", "v")]),
+        ]);
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        assert!(parse_sections("[name]\nno value here\n").is_err());
+    }
+
+    #[test]
+    fn later_values_replace_earlier_ones() {
+        let parsed = parse_sections("a = 1\na = 2\n").unwrap();
+        assert_eq!(parsed[0].get("a"), Some("2"));
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        let mut id = Id::default();
+        for (idx, byte) in id.iter_mut().enumerate() {
+            *byte = (idx * 17) as u8;
+        }
+
+        assert_eq!(decode_id(&encode_id(&id)).unwrap(), id);
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        let id = encode_id(&Id::default());
+
+        assert!(decode_id(&id[1..]).is_err());
+        assert!(decode_id(&id.replacen('0', "g", 1)).is_err());
+        assert!(decode_id(&id.replacen("00", "ё", 1)).is_err());
+    }
+}
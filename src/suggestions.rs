@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use libbdgt::storage::{Id, Transaction};
+
+
+/// Usage statistics of a single description.
+#[derive(Default)]
+struct Usage {
+    /// Description as it was entered the last time.
+    description: String,
+
+    /// Number of transactions with the description.
+    count: usize,
+
+    /// Number of transactions with the description by category.
+    categories: HashMap<Id, usize>,
+
+    /// Number of transactions with the description by account.
+    accounts: HashMap<Id, usize>,
+}
+
+
+/// Suggestions for transaction input based on earlier transactions.
+///
+/// Descriptions are compared case-insensitively, so `Coffee` and
+/// `coffee` are treated as the same payee.
+pub(crate) struct Suggestions {
+    /// Usage statistics by lowercase description.
+    usages: HashMap<String, Usage>,
+}
+
+
+impl Suggestions {
+    /// Collects suggestions from earlier transactions.
+    ///
+    /// * `transactions` - transactions to learn from
+    pub(crate) fn collect(transactions: &[Transaction]) -> Self {
+        let mut suggestions = Suggestions {
+            usages: HashMap::new()
+        };
+
+        for transaction in transactions {
+            suggestions.record(transaction);
+        }
+
+        suggestions
+    }
+
+    /// Takes a new transaction into account.
+    ///
+    /// * `transaction` - transaction to learn from
+    pub(crate) fn record(&mut self, transaction: &Transaction) {
+        let usage = self.usages
+            .entry(transaction.description.to_lowercase())
+            .or_default();
+
+        usage.description = transaction.description.clone();
+        usage.count += 1;
+
+        *usage.categories.entry(transaction.category_id).or_default() += 1;
+        *usage.accounts.entry(transaction.account_id).or_default() += 1;
+    }
+
+    /// Returns known descriptions, the most frequent first.
+    pub(crate) fn descriptions(&self) -> Vec<String> {
+        let mut usages: Vec<_> = self.usages
+            .values()
+            .collect();
+
+        usages.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.description.cmp(&b.description)));
+
+        usages
+            .into_iter()
+            .map(|usage| usage.description.clone())
+            .collect()
+    }
+
+    /// Returns a category most often used with a description.
+    ///
+    /// * `description` - description to look for
+    pub(crate) fn category_for(&self, description: &str) -> Option<Id> {
+        self.usages
+            .get(&description.to_lowercase())
+            .and_then(|usage| Self::most_frequent(&usage.categories))
+    }
+
+    /// Returns an account most often used with a description.
+    ///
+    /// * `description` - description to look for
+    pub(crate) fn account_for(&self, description: &str) -> Option<Id> {
+        self.usages
+            .get(&description.to_lowercase())
+            .and_then(|usage| Self::most_frequent(&usage.accounts))
+    }
+
+    fn most_frequent(counts: &HashMap<Id, usize>) -> Option<Id> {
+        counts
+            .iter()
+            .max_by(|(a_id, a), (b_id, b)| a.cmp(b).then_with(|| b_id.cmp(a_id)))
+            .map(|(id, _)| *id)
+    }
+}
@@ -0,0 +1,159 @@
+use libbdgt::storage::Id;
+
+use crate::error::{Result, Error};
+use crate::store::{self, Section};
+use crate::binding;
+use crate::errors;
+
+
+/// Name of a local file with templates.
+const TEMPLATES_FILE: &str = "templates";
+
+
+/// Named transaction template. Blank fields are requested on use.
+#[derive(Clone, Default)]
+pub(crate) struct Template {
+    /// Name of the template.
+    pub name: String,
+
+    /// Description of transactions.
+    pub description: Option<String>,
+
+    /// Account of transactions.
+    pub account: Option<Id>,
+
+    /// Category of transactions.
+    pub category: Option<Id>,
+
+    /// Default amount of money (sign is assigned by category).
+    pub amount: Option<isize>,
+}
+
+
+/// Collection of templates stored in a local file.
+/// 
+/// Templates are not synchronized, since they are 
+/// a matter of personal convenience.
+pub(crate) struct Templates {
+    /// All templates in order of creation.
+    templates: Vec<Template>,
+}
+
+
+impl Templates {
+    /// Loads templates from storage root.
+    /// 
+    /// * `budget` - budget, which key protects templates
+    pub(crate) fn load(budget: &binding::Budget) -> Result<Self> {
+        let templates = store::read_protected_sections(budget, TEMPLATES_FILE)?
+            .iter()
+            .filter(|section| !section.name.is_empty())
+            .map(Self::from_section)
+            .collect::<Result<_>>()?;
+
+        Ok(Templates {
+            templates: templates
+        })
+    }
+
+    /// Saves templates into storage root.
+    /// 
+    /// * `budget` - budget, which key protects templates
+    pub(crate) fn save(&self, budget: &binding::Budget) -> Result<()> {
+        let sections: Vec<_> = self.templates
+            .iter()
+            .map(Self::to_section)
+            .collect();
+
+        store::write_protected_sections(budget, TEMPLATES_FILE, &sections)
+    }
+
+    /// Returns all templates.
+    pub(crate) fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// Looks for a template by name (case-insensitive).
+    /// 
+    /// * `name` - name of template to look for
+    pub(crate) fn find(&self, name: &str) -> Result<&Template> {
+        self.templates
+            .iter()
+            .find(|template| template.name.to_lowercase() == name.to_lowercase())
+            .ok_or(Error::from_message_with_extra(errors::TEMPLATE_NOT_FOUND, name))
+    }
+
+    /// Adds a new template. Names of templates must be unique and 
+    /// non-blank.
+    /// 
+    /// * `template` - template to add
+    pub(crate) fn add(&mut self, template: Template) -> Result<()> {
+        //
+        // Name is stored as a section header in templates file,
+        // blank one would make the template nameless
+        //
+
+        if template.name.trim().is_empty() || template.name.contains(['[', ']']) {
+            return Err(Error::from_message_with_extra(errors::INVALID_TEMPLATE_NAME, template.name));
+        }
+
+        if self.find(&template.name).is_ok() {
+            return Err(Error::from_message_with_extra(errors::TEMPLATE_EXISTS, template.name));
+        }
+
+        self.templates.push(template);
+
+        Ok(())
+    }
+
+    /// Removes a template by name.
+    /// 
+    /// * `name` - name of template to remove
+    pub(crate) fn remove(&mut self, name: &str) -> Result<()> {
+        let index = self.templates
+            .iter()
+            .position(|template| template.name.to_lowercase() == name.to_lowercase())
+            .ok_or(Error::from_message_with_extra(errors::TEMPLATE_NOT_FOUND, name))?;
+
+        self.templates.remove(index);
+
+        Ok(())
+    }
+
+    fn from_section(section: &Section) -> Result<Template> {
+        let amount = section.get("amount")
+            .map(|amount| amount.parse::<isize>())
+            .transpose()
+            .map_err(|_| Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, TEMPLATES_FILE))?;
+
+        Ok(Template {
+            name: section.name.clone(),
+            description: section.get("description").map(str::to_owned),
+            account: section.get("account").map(store::decode_id).transpose()?,
+            category: section.get("category").map(store::decode_id).transpose()?,
+            amount: amount
+        })
+    }
+
+    fn to_section(template: &Template) -> Section {
+        let mut section = Section::new(template.name.clone());
+
+        if let Some(description) = &template.description {
+            section.set("description", description.clone());
+        }
+
+        if let Some(account) = &template.account {
+            section.set("account", store::encode_id(account));
+        }
+
+        if let Some(category) = &template.category {
+            section.set("category", store::encode_id(category));
+        }
+
+        if let Some(amount) = template.amount {
+            section.set("amount", amount.to_string());
+        }
+
+        section
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+
+use libbdgt::datetime::Timestamp;
+use libbdgt::storage::{Id, Transaction};
+
+use crate::error::{Result, Error};
+use crate::store::{self, Section};
+use crate::binding;
+use crate::errors;
+
+
+/// Name of a local file with transfers made on this device.
+const TRANSFERS_FILE: &str = "transfers";
+
+
+/// Role of a transaction in a transfer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    /// Money leaves an account.
+    Outgoing,
+
+    /// Money comes to an account.
+    Incoming,
+
+    /// Difference between sent and received amounts.
+    Adjustment,
+
+    /// Fee charged for a transfer. Fee is a spending, not a transfer.
+    Fee,
+}
+
+
+/// Transaction, that is a part of a transfer.
+pub(crate) struct Leg {
+    /// Role of the transaction.
+    pub role: Role,
+
+    /// Account on the other side of the transfer.
+    pub counterpart: Id,
+}
+
+
+/// Single transfer between two accounts.
+pub(crate) struct TransferInfo {
+    /// Timestamp of the transfer.
+    pub timestamp: Timestamp,
+
+    /// Account, that money is transferred from.
+    pub from: Id,
+
+    /// Account, that money is transferred to.
+    pub to: Id,
+
+    /// Amount of money, that left source account.
+    pub sent: isize,
+
+    /// Amount of money, that came to destination account.
+    pub received: isize,
+
+    /// Fee charged for the transfer.
+    pub fee: isize,
+
+    /// Identifiers of all linked transactions (including fees).
+    pub transactions: Vec<Id>,
+}
+
+
+/// Identifiers of transactions created for a single transfer.
+pub(crate) struct RecordedTransfer {
+    /// Transaction in source account.
+    pub outgoing: Id,
+
+    /// Transaction in destination account.
+    pub incoming: Id,
+
+    /// Transaction, that compensates difference between sent and received amounts.
+    pub adjustment: Option<Id>,
+
+    /// Fee transaction.
+    pub fee: Option<Id>,
+}
+
+
+/// Transfers made on this device.
+///
+/// Storage does not mark transactions created by
+/// [`crate::binding::Budget::add_transfer`], hence `transfer` command
+/// records identifiers of all transactions of a transfer here. Transfers
+/// made on other instances are not known, hence their transactions are
+/// treated as ordinary ones.
+pub(crate) struct Registry {
+    /// Recorded transfers in order of creation.
+    transfers: Vec<RecordedTransfer>,
+}
+
+
+/// Links between transactions, that belong to transfers.
+///
+/// Only transactions recorded in [`Registry`] are linked. Transfers
+/// are restored if both outgoing and incoming transactions are present.
+pub(crate) struct Transfers {
+    /// Legs of transfers by transaction identifiers.
+    legs: HashMap<Id, Leg>,
+
+    /// All transfers in chronological order.
+    transfers: Vec<TransferInfo>,
+}
+
+
+impl Registry {
+    /// Loads recorded transfers from storage root.
+    ///
+    /// * `budget` - budget, which key protects recorded transfers
+    pub(crate) fn load(budget: &binding::Budget) -> Result<Self> {
+        Self::from_sections(&store::read_protected_sections(budget, TRANSFERS_FILE)?)
+    }
+
+    /// Saves recorded transfers into storage root.
+    ///
+    /// * `budget` - budget, which key protects recorded transfers
+    pub(crate) fn save(&self, budget: &binding::Budget) -> Result<()> {
+        store::write_protected_sections(budget, TRANSFERS_FILE, &self.to_sections())
+    }
+
+    /// Creates registry from sections of a local file.
+    ///
+    /// * `sections` - sections to read transfers from
+    pub(crate) fn from_sections(sections: &[Section]) -> Result<Self> {
+        let transfers = sections
+            .iter()
+            .filter(|section| !section.name.is_empty())
+            .map(Self::from_section)
+            .collect::<Result<_>>()?;
+
+        Ok(Registry {
+            transfers: transfers
+        })
+    }
+
+    /// Converts registry into sections of a local file.
+    pub(crate) fn to_sections(&self) -> Vec<Section> {
+        self.transfers
+            .iter()
+            .map(Self::to_section)
+            .collect()
+    }
+
+    /// Returns all recorded transfers.
+    pub(crate) fn transfers(&self) -> &[RecordedTransfer] {
+        &self.transfers
+    }
+
+    /// Records a transfer.
+    ///
+    /// * `transfer` - identifiers of transactions of the transfer
+    pub(crate) fn add(&mut self, transfer: RecordedTransfer) {
+        self.transfers.push(transfer);
+    }
+
+    /// Updates identifiers of transactions, that were re-created.
+    ///
+    /// * `replaced` - pairs of old and new identifiers
+    pub(crate) fn replace_ids(&mut self, replaced: &[(Id, Id)]) {
+        let replace = |id: &mut Id| {
+            if let Some((_, new_id)) = replaced.iter().find(|(old_id, _)| old_id == id) {
+                *id = *new_id;
+            }
+        };
+
+        for transfer in &mut self.transfers {
+            replace(&mut transfer.outgoing);
+            replace(&mut transfer.incoming);
+            transfer.adjustment.iter_mut().for_each(replace);
+            transfer.fee.iter_mut().for_each(replace);
+        }
+    }
+
+    fn from_section(section: &Section) -> Result<RecordedTransfer> {
+        let error = || Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, TRANSFERS_FILE);
+        let get = |key: &str| section.get(key).map(store::decode_id).transpose();
+
+        Ok(RecordedTransfer {
+            outgoing: get("outgoing")?.ok_or_else(error)?,
+            incoming: get("incoming")?.ok_or_else(error)?,
+            adjustment: get("adjustment")?,
+            fee: get("fee")?
+        })
+    }
+
+    fn to_section(transfer: &RecordedTransfer) -> Section {
+        let mut section = Section::new("transfer");
+        section.set("outgoing", store::encode_id(&transfer.outgoing));
+        section.set("incoming", store::encode_id(&transfer.incoming));
+
+        if let Some(adjustment) = &transfer.adjustment {
+            section.set("adjustment", store::encode_id(adjustment));
+        }
+
+        if let Some(fee) = &transfer.fee {
+            section.set("fee", store::encode_id(fee));
+        }
+
+        section
+    }
+}
+
+
+/// Records a transfer in the registry in storage root.
+///
+/// * `budget` - budget, which key protects recorded transfers
+/// * `transfer` - identifiers of transactions of the transfer
+pub(crate) fn record(budget: &binding::Budget, transfer: RecordedTransfer) -> Result<()> {
+    let mut registry = Registry::load(budget)?;
+    registry.add(transfer);
+    registry.save(budget)
+}
+
+
+/// Updates identifiers of re-created transactions in the registry in storage root.
+///
+/// * `budget` - budget, which key protects recorded transfers
+/// * `replaced` - pairs of old and new identifiers
+pub(crate) fn replace_ids(budget: &binding::Budget, replaced: &[(Id, Id)]) -> Result<()> {
+    let mut registry = Registry::load(budget)?;
+    registry.replace_ids(replaced);
+    registry.save(budget)
+}
+
+
+impl Transfers {
+    /// Restores transfers recorded in registry from a set of transactions.
+    ///
+    /// Transactions of all accounts must be passed here, otherwise
+    /// some legs will not be found.
+    ///
+    /// * `transactions` - transactions to look for transfers in
+    /// * `registry` - recorded transfers
+    pub(crate) fn link(transactions: &[Transaction], registry: &Registry) -> Self {
+        let by_id: HashMap<_, _> = transactions
+            .iter()
+            .filter_map(|transaction| transaction.id.map(|id| (id, transaction)))
+            .collect();
+
+        let mut legs = HashMap::new();
+        let mut transfers = Vec::new();
+
+        for recorded in registry.transfers() {
+            let (outgoing, incoming) = match (by_id.get(&recorded.outgoing), by_id.get(&recorded.incoming)) {
+                (Some(outgoing), Some(incoming)) => (outgoing, incoming),
+                _ => continue
+            };
+
+            legs.insert(recorded.outgoing, Leg {
+                role: Role::Outgoing,
+                counterpart: incoming.account_id
+            });
+
+            legs.insert(recorded.incoming, Leg {
+                role: Role::Incoming,
+                counterpart: outgoing.account_id
+            });
+
+            let mut transfer = TransferInfo {
+                timestamp: outgoing.timestamp,
+                from: outgoing.account_id,
+                to: incoming.account_id,
+                sent: incoming.amount,
+                received: incoming.amount,
+                fee: 0,
+                transactions: vec![recorded.outgoing, recorded.incoming]
+            };
+
+            //
+            // Source account is charged with received amount, the rest
+            // is compensated by adjustment. Fee is charged separately.
+            //
+
+            let extra = [(recorded.adjustment, Role::Adjustment), (recorded.fee, Role::Fee)];
+
+            for (id, role) in extra {
+                let transaction = match id.and_then(|id| by_id.get(&id)) {
+                    Some(transaction) => transaction,
+                    None => continue
+                };
+
+                match role {
+                    Role::Adjustment => transfer.sent -= transaction.amount,
+                    _ => transfer.fee -= transaction.amount
+                };
+
+                transfer.transactions.push(transaction.id.unwrap());
+
+                legs.insert(transaction.id.unwrap(), Leg {
+                    role: role,
+                    counterpart: transfer.to
+                });
+            }
+
+            transfers.push(transfer);
+        }
+
+        transfers.sort_by_key(|transfer| transfer.timestamp);
+
+        Transfers {
+            legs: legs,
+            transfers: transfers
+        }
+    }
+
+    /// Returns transfer leg information for a transaction if it is a part of a transfer.
+    ///
+    /// * `transaction` - transaction to query information for
+    pub(crate) fn leg(&self, transaction: &Transaction) -> Option<&Leg> {
+        transaction.id
+            .and_then(|id| self.legs.get(&id))
+    }
+
+    /// Checks if a transaction is a part of a transfer. Fees are not
+    /// treated as transfers, since they are spendings.
+    ///
+    /// * `transaction` - transaction to check
+    pub(crate) fn is_transfer(&self, transaction: &Transaction) -> bool {
+        self.leg(transaction)
+            .is_some_and(|leg| leg.role != Role::Fee)
+    }
+
+    /// Returns all found transfers in chronological order.
+    pub(crate) fn transfers(&self) -> &[TransferInfo] {
+        &self.transfers
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use libbdgt::storage::MetaInfo;
+
+    use super::*;
+
+    fn id(value: u8) -> Id {
+        let mut id = Id::default();
+        id[0] = value;
+        id
+    }
+
+    fn transaction(value: u8, account: u8, category: u8, amount: isize) -> Transaction {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:00:00+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        Transaction {
+            id: Some(id(value)),
+            timestamp: timestamp,
+            description: String::new(),
+            account_id: id(account),
+            category_id: id(category),
+            amount: amount,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::from_sections(&[]).unwrap();
+        registry.add(RecordedTransfer {
+            outgoing: id(1),
+            incoming: id(2),
+            adjustment: Some(id(3)),
+            fee: Some(id(4))
+        });
+        registry
+    }
+
+    #[test]
+    fn unrecorded_transactions_are_not_linked() {
+        let transactions = [
+            transaction(5, 100, 200, -300),
+            transaction(6, 101, 201, 300),
+        ];
+
+        let transfers = Transfers::link(&transactions, &registry());
+
+        assert!(transfers.transfers().is_empty());
+        assert!(transactions.iter().all(|transaction| transfers.leg(transaction).is_none()));
+    }
+
+    #[test]
+    fn recorded_transactions_are_linked() {
+        let transactions = [
+            transaction(1, 100, 200, -300),
+            transaction(2, 101, 201, 300),
+            transaction(3, 100, 200, -20),
+            transaction(4, 100, 202, -5),
+            transaction(5, 100, 200, -300),
+        ];
+
+        let transfers = Transfers::link(&transactions, &registry());
+
+        let transfer = &transfers.transfers()[0];
+        assert_eq!(transfers.transfers().len(), 1);
+        assert_eq!((transfer.from, transfer.to), (id(100), id(101)));
+        assert_eq!((transfer.sent, transfer.received, transfer.fee), (320, 300, 5));
+
+        let roles: Vec<_> = transactions
+            .iter()
+            .map(|transaction| transfers.leg(transaction).map(|leg| leg.role))
+            .collect();
+
+        assert!(roles == [Some(Role::Outgoing), Some(Role::Incoming), Some(Role::Adjustment), Some(Role::Fee), None]);
+        assert!(!transfers.is_transfer(&transactions[3]));
+    }
+
+    #[test]
+    fn transfers_without_both_sides_are_skipped() {
+        let transactions = [transaction(1, 100, 200, -300)];
+
+        assert!(Transfers::link(&transactions, &registry()).transfers().is_empty());
+    }
+
+    #[test]
+    fn registry_round_trip() {
+        let mut registry = registry();
+        registry.add(RecordedTransfer {
+            outgoing: id(7),
+            incoming: id(8),
+            adjustment: None,
+            fee: None
+        });
+
+        let parsed = Registry::from_sections(&registry.to_sections()).unwrap();
+
+        let ids = |registry: &Registry| -> Vec<_> {
+            registry.transfers()
+                .iter()
+                .map(|transfer| (transfer.outgoing, transfer.incoming, transfer.adjustment, transfer.fee))
+                .collect()
+        };
+
+        assert_eq!(ids(&parsed), ids(&registry));
+    }
+
+    #[test]
+    fn recreated_transactions_stay_recorded() {
+        let mut registry = registry();
+        registry.replace_ids(&[(id(2), id(9)), (id(4), id(10))]);
+
+        let transfer = &registry.transfers()[0];
+        assert_eq!((transfer.incoming, transfer.fee), (id(9), Some(id(10))));
+    }
+}
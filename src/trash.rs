@@ -0,0 +1,587 @@
+use std::collections::HashSet;
+
+use libbdgt::datetime::{Clock, Timestamp};
+use libbdgt::storage::{Account, Category, CategoryType, Id, MetaInfo, Plan, Transaction};
+
+use crate::error::{Result, Error};
+use crate::store::{self, Section};
+use crate::journal::{self, Operation};
+use crate::binding;
+use crate::errors;
+
+
+/// Name of a local file with removed items.
+const TRASH_FILE: &str = "trash";
+
+
+/// Removed item.
+pub(crate) enum Item {
+    /// Removed account.
+    Account(Account),
+
+    /// Removed category.
+    Category(Category),
+
+    /// Removed plan.
+    Plan(Plan),
+
+    /// Removed transaction.
+    Transaction(Transaction),
+}
+
+
+/// Removed item with its removal time.
+pub(crate) struct Entry {
+    /// Snapshot of the item taken before removal.
+    pub item: Item,
+
+    /// Time of removal.
+    pub removed: Timestamp,
+}
+
+
+/// Snapshots of items removed on this device.
+/// 
+/// Removed items are not returned by [`binding::Budget`] queries,
+/// hence removal commands save snapshots of items here before 
+/// removing them. Snapshots are encrypted with the instance's key
+/// like the rest of the storage. Restored items are added as new ones, since
+/// removal cannot be reverted in the storage itself. References
+/// to restored items from other snapshots are updated accordingly.
+pub(crate) struct Trash {
+    /// Removed items in order of removal.
+    entries: Vec<Entry>,
+}
+
+
+impl Trash {
+    /// Loads removed items from storage root.
+    /// 
+    /// * `budget` - budget, which key protects removed items
+    pub(crate) fn load(budget: &binding::Budget) -> Result<Self> {
+        Self::from_sections(&store::read_protected_sections(budget, TRASH_FILE)?)
+    }
+
+    /// Saves removed items into storage root.
+    /// 
+    /// * `budget` - budget, which key protects removed items
+    pub(crate) fn save(&self, budget: &binding::Budget) -> Result<()> {
+        store::write_protected_sections(budget, TRASH_FILE, &self.to_sections())
+    }
+
+    /// Creates trash from sections of a local file.
+    /// 
+    /// * `sections` - sections to read removed items from
+    pub(crate) fn from_sections(sections: &[Section]) -> Result<Self> {
+        let entries = sections
+            .iter()
+            .filter(|section| !section.name.is_empty())
+            .map(Self::from_section)
+            .collect::<Result<_>>()?;
+
+        Ok(Trash {
+            entries: entries
+        })
+    }
+
+    /// Converts removed items into sections of a local file.
+    pub(crate) fn to_sections(&self) -> Vec<Section> {
+        self.entries
+            .iter()
+            .map(Self::to_section)
+            .collect()
+    }
+
+    /// Returns all removed items.
+    pub(crate) fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Forgets all removed items. Items remain removed.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Removes an account and saves its snapshot. If transactions
+    /// are removed as well, their snapshots are saved too.
+    /// 
+    /// * `budget` - budget to remove account from
+    /// * `account` - account to remove
+    /// * `force` - remove account with all its transactions
+    pub(crate) fn remove_account(&mut self, budget: &binding::Budget, account: &Account, force: bool) -> Result<()> {
+        let id = account.id.unwrap();
+        let removed = Clock::now();
+
+        let transactions = match force {
+            true => budget.transactions_of(id)?,
+            false => Vec::new()
+        };
+
+        budget.remove_account(id, force, removed)?;
+
+        self.put(Item::Account(Self::copy_account(account)), removed);
+        for transaction in transactions {
+            self.put(Item::Transaction(transaction), removed);
+        }
+
+        journal::record(budget, Operation::RemoveAccount(id), format!("Remove account '{}'", account.name))
+    }
+
+    /// Removes a category and saves its snapshot.
+    /// 
+    /// * `budget` - budget to remove category from
+    /// * `category` - category to remove
+    pub(crate) fn remove_category(&mut self, budget: &binding::Budget, category: &Category) -> Result<()> {
+        let removed = Clock::now();
+        budget.remove_category(category.id.unwrap(), removed)?;
+
+        self.put(Item::Category(Self::copy_category(category)), removed);
+
+        journal::record(budget, Operation::RemoveCategory(category.id.unwrap()), 
+            format!("Remove category '{}'", category.name))
+    }
+
+    /// Removes a plan and saves its snapshot.
+    /// 
+    /// * `budget` - budget to remove plan from
+    /// * `plan` - plan to remove
+    pub(crate) fn remove_plan(&mut self, budget: &binding::Budget, plan: &Plan) -> Result<()> {
+        let removed = Clock::now();
+        budget.remove_plan(plan.id.unwrap(), removed)?;
+
+        self.put(Item::Plan(Self::copy_plan(plan)), removed);
+
+        journal::record(budget, Operation::RemovePlan(plan.id.unwrap()), format!("Remove plan '{}'", plan.name))
+    }
+
+    /// Removes a transaction and saves its snapshot.
+    /// 
+    /// * `budget` - budget to remove transaction from
+    /// * `transaction` - transaction to remove
+    pub(crate) fn remove_transaction(&mut self, budget: &binding::Budget, transaction: &Transaction) -> Result<()> {
+        let removed = Clock::now();
+        budget.remove_transaction(transaction.id.unwrap(), removed)?;
+
+        self.put(Item::Transaction(Self::copy_transaction(transaction)), removed);
+
+        journal::record(budget, Operation::RemoveTransaction(transaction.id.unwrap()), 
+            format!("Remove transaction '{}'", transaction.description))
+    }
+
+    /// Restores removed items by their indices.
+    /// 
+    /// Accounts and categories are restored first, so plans and
+    /// transactions restored at the same time can refer to them.
+    /// Items, that cannot be restored, are reported and kept.
+    /// Returns pairs of old and new identifiers of restored items.
+    /// 
+    /// * `budget` - budget to restore items into
+    /// * `indices` - indices of entries to restore
+    pub(crate) fn restore(&mut self, budget: &binding::Budget, indices: &[usize]) -> Result<Vec<(Id, Id)>> {
+        let mut indices = indices.to_vec();
+        indices.sort_by_key(|idx| match self.entries[*idx].item {
+            Item::Account(_) | Item::Category(_) => 0,
+            Item::Plan(_) => 1,
+            Item::Transaction(_) => 2
+        });
+
+        let mut restored = HashSet::new();
+        let mut replaced = Vec::new();
+        for idx in indices {
+            match self.restore_entry(budget, idx) {
+                Ok(ids) => { 
+                    restored.insert(idx);
+                    replaced.push(ids);
+                },
+                Err(e) => eprintln!("Cannot restore {}: {}", Self::describe(&self.entries[idx].item), e)
+            }
+        }
+
+        let mut idx = 0;
+        self.entries.retain(|_| {
+            idx += 1;
+            !restored.contains(&(idx - 1))
+        });
+
+        Ok(replaced)
+    }
+
+    /// Returns indices of entries removed by the latest removal of
+    /// an item. Transactions removed along with an account are included.
+    /// 
+    /// * `id` - identifier of removed item
+    pub(crate) fn removed_with(&self, id: Id) -> Vec<usize> {
+        let found = self.entries
+            .iter()
+            .rposition(|entry| match &entry.item {
+                Item::Account(account) => account.id == Some(id),
+                Item::Category(category) => category.id == Some(id),
+                Item::Plan(plan) => plan.id == Some(id),
+                Item::Transaction(transaction) => transaction.id == Some(id)
+            });
+
+        let found = match found {
+            Some(found) => found,
+            None => return Vec::new()
+        };
+
+        let removed = self.entries[found].removed;
+
+        let mut indices = vec![found];
+        indices.extend(self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| match &entry.item {
+                Item::Transaction(transaction) => transaction.account_id == id && entry.removed == removed,
+                _ => false
+            })
+            .map(|(idx, _)| idx));
+
+        indices
+    }
+
+    /// Returns printable kind and name of an item.
+    /// 
+    /// * `item` - item to describe
+    pub(crate) fn describe(item: &Item) -> String {
+        match item {
+            Item::Account(account) => format!("account '{}'", account.name),
+            Item::Category(category) => format!("category '{}'", category.name),
+            Item::Plan(plan) => format!("plan '{}'", plan.name),
+            Item::Transaction(transaction) => format!("transaction '{}'", transaction.description)
+        }
+    }
+
+    fn restore_entry(&mut self, budget: &binding::Budget, idx: usize) -> Result<(Id, Id)> {
+        let (old_id, new_id) = match &self.entries[idx].item {
+            Item::Account(account) => {
+                let known = journal::ids(budget.accounts()?.iter().map(|account| account.id));
+
+                let mut copy = Self::copy_account(account);
+                copy.id = None;
+                copy.balance = copy.initial_balance;
+                copy.meta_info = MetaInfo::new(Some(Clock::now()), None, None);
+
+                budget.add_account(&copy)?;
+
+                (account.id, journal::added_id(&known, budget.accounts()?.iter().map(|account| account.id))?)
+            },
+            Item::Category(category) => {
+                let known = journal::ids(budget.categories()?.iter().map(|category| category.id));
+
+                let mut copy = Self::copy_category(category);
+                copy.id = None;
+                copy.meta_info = MetaInfo::new(Some(Clock::now()), None, None);
+
+                budget.add_category(&copy)?;
+
+                (category.id, journal::added_id(&known, budget.categories()?.iter().map(|category| category.id))?)
+            },
+            Item::Plan(plan) => {
+                Self::ensure_category_exists(budget, plan.category_id)?;
+
+                let known = journal::ids(budget.plans()?.iter().map(|plan| plan.id));
+
+                let mut copy = Self::copy_plan(plan);
+                copy.id = None;
+                copy.meta_info = MetaInfo::new(Some(Clock::now()), None, None);
+
+                budget.add_plan(&copy)?;
+
+                (plan.id, journal::added_id(&known, budget.plans()?.iter().map(|plan| plan.id))?)
+            },
+            Item::Transaction(transaction) => {
+                //
+                // Balance of the account is updated by the budget
+                // when transaction is added
+                //
+
+                Self::ensure_category_exists(budget, transaction.category_id)?;
+
+                let accounts = journal::ids(budget.accounts()?.iter().map(|account| account.id));
+                if !accounts.contains(&transaction.account_id) {
+                    return Err(Error::from_message(errors::MISSING_DEPENDENCY));
+                }
+
+                let transactions_of = || budget.transactions_of(transaction.account_id);
+                let known = journal::ids(transactions_of()?.iter().map(|transaction| transaction.id));
+
+                let mut copy = Self::copy_transaction(transaction);
+                copy.id = None;
+                copy.meta_info = MetaInfo::new(Some(Clock::now()), None, None);
+
+                budget.add_transaction(&copy)?;
+
+                (transaction.id, journal::added_id(&known, transactions_of()?.iter().map(|transaction| transaction.id))?)
+            }
+        };
+
+        let old_id = old_id.unwrap_or_default();
+        self.replace_references(old_id, new_id);
+
+        Ok((old_id, new_id))
+    }
+
+    fn ensure_category_exists(budget: &binding::Budget, category_id: Id) -> Result<()> {
+        //
+        // Only existing (not removed) categories are listed
+        //
+
+        let categories = journal::ids(budget.categories()?.iter().map(|category| category.id));
+
+        match categories.contains(&category_id) {
+            true => Ok(()),
+            false => Err(Error::from_message(errors::MISSING_DEPENDENCY))
+        }
+    }
+
+    fn replace_references(&mut self, old_id: Id, new_id: Id) {
+        for entry in &mut self.entries {
+            match &mut entry.item {
+                Item::Plan(plan) if plan.category_id == old_id => plan.category_id = new_id,
+                Item::Transaction(transaction) => {
+                    if transaction.account_id == old_id {
+                        transaction.account_id = new_id;
+                    }
+
+                    if transaction.category_id == old_id {
+                        transaction.category_id = new_id;
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn put(&mut self, item: Item, removed: Timestamp) {
+        self.entries.push(Entry {
+            item: item,
+            removed: removed
+        });
+    }
+}
+
+
+impl Trash {
+    fn from_section(section: &Section) -> Result<Entry> {
+        let error = || Error::from_message_with_extra(errors::INVALID_LOCAL_FILE, TRASH_FILE);
+
+        let (kind, id) = section.name
+            .split_once(':')
+            .ok_or_else(error)?;
+
+        let id = Some(store::decode_id(id)?);
+        let get = |key: &str| section.get(key).ok_or_else(error);
+        let get_amount = |key: &str| get(key)?.parse::<isize>().map_err(|_| error());
+        let get_timestamp = |key: &str| chrono::DateTime::parse_from_rfc3339(get(key)?)
+            .map(|timestamp| timestamp.with_timezone(&chrono::Utc))
+            .map_err(|_| error());
+
+        let meta_info = || MetaInfo::new(None, None, None);
+
+        let item = match kind {
+            "account" => Item::Account(Account {
+                id: id,
+                name: get("name")?.to_owned(),
+                balance: get_amount("initial_balance")?,
+                initial_balance: get_amount("initial_balance")?,
+                meta_info: meta_info()
+            }),
+            "category" => Item::Category(Category {
+                id: id,
+                name: get("name")?.to_owned(),
+                category_type: match get("type")? {
+                    "income" => CategoryType::Income,
+                    _ => CategoryType::Outcome
+                },
+                meta_info: meta_info()
+            }),
+            "plan" => Item::Plan(Plan {
+                id: id,
+                category_id: store::decode_id(get("category")?)?,
+                name: get("name")?.to_owned(),
+                amount_limit: get_amount("amount_limit")?,
+                meta_info: meta_info()
+            }),
+            "transaction" => Item::Transaction(Transaction {
+                id: id,
+                timestamp: get_timestamp("timestamp")?,
+                description: get("description")?.to_owned(),
+                account_id: store::decode_id(get("account")?)?,
+                category_id: store::decode_id(get("category")?)?,
+                amount: get_amount("amount")?,
+                meta_info: meta_info()
+            }),
+            _ => return Err(error())
+        };
+
+        Ok(Entry {
+            item: item,
+            removed: get_timestamp("removed")?
+        })
+    }
+
+    fn to_section(entry: &Entry) -> Section {
+        let (kind, id) = match &entry.item {
+            Item::Account(account) => ("account", account.id),
+            Item::Category(category) => ("category", category.id),
+            Item::Plan(plan) => ("plan", plan.id),
+            Item::Transaction(transaction) => ("transaction", transaction.id)
+        };
+
+        let mut section = Section::new(format!("{}:{}", kind, store::encode_id(&id.unwrap_or_default())));
+
+        match &entry.item {
+            Item::Account(account) => {
+                section.set("name", account.name.clone());
+                section.set("initial_balance", account.initial_balance.to_string());
+            },
+            Item::Category(category) => {
+                section.set("name", category.name.clone());
+                section.set("type", match category.category_type {
+                    CategoryType::Income => "income",
+                    CategoryType::Outcome => "outcome"
+                });
+            },
+            Item::Plan(plan) => {
+                section.set("name", plan.name.clone());
+                section.set("category", store::encode_id(&plan.category_id));
+                section.set("amount_limit", plan.amount_limit.to_string());
+            },
+            Item::Transaction(transaction) => {
+                section.set("timestamp", transaction.timestamp.to_rfc3339());
+                section.set("description", transaction.description.clone());
+                section.set("account", store::encode_id(&transaction.account_id));
+                section.set("category", store::encode_id(&transaction.category_id));
+                section.set("amount", transaction.amount.to_string());
+            }
+        }
+
+        section.set("removed", entry.removed.to_rfc3339());
+        section
+    }
+
+    fn copy_account(account: &Account) -> Account {
+        Account {
+            id: account.id,
+            name: account.name.clone(),
+            balance: account.balance,
+            initial_balance: account.initial_balance,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+
+    fn copy_category(category: &Category) -> Category {
+        Category {
+            id: category.id,
+            name: category.name.clone(),
+            category_type: category.category_type,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+
+    fn copy_transaction(transaction: &Transaction) -> Transaction {
+        Transaction {
+            id: transaction.id,
+            timestamp: transaction.timestamp,
+            description: transaction.description.clone(),
+            account_id: transaction.account_id,
+            category_id: transaction.category_id,
+            amount: transaction.amount,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+
+    fn copy_plan(plan: &Plan) -> Plan {
+        Plan {
+            id: plan.id,
+            category_id: plan.category_id,
+            name: plan.name.clone(),
+            amount_limit: plan.amount_limit,
+            meta_info: MetaInfo::new(None, None, None)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(value: u8) -> Id {
+        let mut id = Id::default();
+        id[0] = value;
+        id
+    }
+
+    fn trash() -> Trash {
+        let removed = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:00:00+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let mut trash = Trash::from_sections(&[]).unwrap();
+        trash.put(Item::Account(Account {
+            id: Some(id(1)),
+            name: "Cash".to_owned(),
+            balance: 50,
+            initial_balance: 100,
+            meta_info: MetaInfo::new(None, None, None)
+        }), removed);
+        trash.put(Item::Transaction(Transaction {
+            id: Some(id(2)),
+            timestamp: removed,
+            description: "Lunch".to_owned(),
+            account_id: id(1),
+            category_id: id(3),
+            amount: -50,
+            meta_info: MetaInfo::new(None, None, None)
+        }), removed);
+        trash.put(Item::Plan(Plan {
+            id: Some(id(4)),
+            category_id: id(3),
+            name: "Food".to_owned(),
+            amount_limit: 300,
+            meta_info: MetaInfo::new(None, None, None)
+        }), removed + chrono::Duration::seconds(1));
+        trash
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let trash = trash();
+        let parsed = Trash::from_sections(&trash.to_sections()).unwrap();
+
+        let describe = |trash: &Trash| -> Vec<_> {
+            trash.entries()
+                .iter()
+                .map(|entry| (Trash::describe(&entry.item), entry.removed))
+                .collect()
+        };
+
+        assert_eq!(describe(&parsed), describe(&trash));
+
+        for (parsed, entry) in parsed.to_sections().iter().zip(trash.to_sections()) {
+            assert_eq!(parsed.name, entry.name);
+            assert_eq!(parsed.values, entry.values);
+        }
+    }
+
+    #[test]
+    fn transactions_are_removed_with_account() {
+        let trash = trash();
+
+        assert_eq!(trash.removed_with(id(1)), [0, 1]);
+        assert_eq!(trash.removed_with(id(4)), [2]);
+        assert_eq!(trash.removed_with(id(2)), [1]);
+        assert!(trash.removed_with(id(3)).is_empty());
+    }
+
+    #[test]
+    fn references_follow_restored_items() {
+        let mut trash = trash();
+        trash.replace_references(id(3), id(9));
+
+        assert!(matches!(&trash.entries()[1].item, Item::Transaction(transaction) if transaction.category_id == id(9)));
+        assert!(matches!(&trash.entries()[2].item, Item::Plan(plan) if plan.category_id == id(9)));
+    }
+}